@@ -0,0 +1,54 @@
+/// Implements `BranchBuilder` with set membership decisions for a `Clone + Eq + Hash` type.
+#[macro_export]
+macro_rules! eq_implementation {
+    ($t:ident) => {
+        impl $crate::BranchBuilder for $t {
+            type Decision = $crate::EqDecision<Self>;
+
+            fn find_best_decision<'a, R, F, D>(
+                search: &$crate::FeatureSearch,
+                entropy: f64,
+                data: &'a [(D, R)],
+                extract: F,
+            ) -> Self::Decision
+            where
+                R: Copy + Eq + std::hash::Hash + $crate::MaybeSync,
+                F: Fn(&'a D) -> &'a Self + $crate::MaybeSync,
+                Self: 'a,
+                D: $crate::MaybeSync,
+            {
+                let (classes, class_count) = $crate::utils::class_indices(data);
+                let vals = data.iter().zip(classes).map(|((d, _), class)| (extract(d), class));
+
+                let (vals, decision_eval) =
+                    $crate::utils::find_best_values(search.builder.criterion, entropy, vals, class_count);
+                return Self::Decision { vals: vals.into_iter().cloned().collect(), decision_eval };
+            }
+
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return decision.vals.contains(self);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TreeBuilder;
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    enum TestEnum {
+        A,
+        B,
+    }
+
+    eq_implementation!(TestEnum);
+
+    #[test]
+    fn test_enum() {
+        let mut data = [(TestEnum::A, 1), (TestEnum::B, 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&TestEnum::A), 1);
+        assert_eq!(tree.predict(&TestEnum::B), 2);
+    }
+}
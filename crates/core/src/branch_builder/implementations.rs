@@ -0,0 +1,734 @@
+use std::borrow::Cow;
+use std::hash::Hash;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    ord_implementation, parallel, utils, ArrayDecision, BoolDecision, CharClass, CharDecision,
+    Decision, DerefDecision, FeatureSearch, MaybeSend, MaybeSync, NonZeroDecision, StringDecision,
+    VecDecision,
+};
+
+/// Number of leading elements of slices that are searched for decisions on a single element.
+const MAX_INDEXED_ELEMENTS: usize = 16;
+
+/// Allows a type to be used as an input of the `TreeBuilder`.
+pub trait BranchBuilder {
+    /// Decision that can be taken on a value of this type.
+    type Decision: Decision + MaybeSend;
+
+    /// Finds the decision that best splits `data` by the extracted value,
+    /// using the split options of the builder of `search`.
+    /// The extracted values only have to live as long as `data`, so values behind references can be forwarded to.
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+        search: &FeatureSearch,
+        entropy: f64,
+        data: &'a [(D, R)],
+        extract: F,
+    ) -> Self::Decision
+    where
+        F: Fn(&'a D) -> &'a Self + MaybeSync,
+        Self: 'a;
+
+    /// Checks whether the value satisfies the decision.
+    fn matches(&self, decision: &Self::Decision) -> bool;
+
+    /// Reorders `data` so the entries matching `decision` come first and returns their count.
+    fn split_data<F, D, R>(data: &mut [(D, R)], extract: F, decision: &Self::Decision) -> usize
+    where F: Fn(&D) -> &Self {
+        return utils::split_data(data, |(d, _)| extract(d).matches(decision));
+    }
+}
+
+/// Support for strings, with decisions on equality, patterns and length
+macro_rules! string_implementation {
+    ($($t:ty),+) => ($(
+        impl BranchBuilder for $t {
+            type Decision = StringDecision;
+
+            fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+                search: &FeatureSearch,
+                entropy: f64,
+                data: &'a [(D, R)],
+                extract: F,
+            ) -> Self::Decision
+            where
+                F: Fn(&'a D) -> &'a Self + MaybeSync,
+                Self: 'a,
+            {
+                let (classes, class_count) = utils::class_indices(data);
+                let vals: Vec<(&str, usize)> = data.iter().zip(classes).map(|((d, _), class)| (&**extract(d), class)).collect();
+                return utils::find_best_string_decision(search, entropy, &vals, class_count);
+            }
+
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return decision.matches(self);
+            }
+        }
+    )+)
+}
+
+string_implementation!(String, &str, Cow<'_, str>);
+
+ord_implementation!(u8);
+ord_implementation!(u16);
+ord_implementation!(u32);
+ord_implementation!(u64);
+ord_implementation!(u128);
+ord_implementation!(usize);
+
+ord_implementation!(i8);
+ord_implementation!(i16);
+ord_implementation!(i32);
+ord_implementation!(i64);
+ord_implementation!(i128);
+ord_implementation!(isize);
+
+ord_implementation!(f32, float);
+ord_implementation!(f64, float);
+
+ord_implementation!(Duration, |lower: Duration, upper: Duration| {
+    let midpoint = lower + (upper - lower) / 2;
+    return (lower < midpoint).then_some(midpoint);
+});
+
+/// Support for `char`, by code point or by character class
+impl BranchBuilder for char {
+    type Decision = CharDecision;
+
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+        search: &FeatureSearch,
+        entropy: f64,
+        data: &'a [(D, R)],
+        extract: F,
+    ) -> Self::Decision
+    where
+        F: Fn(&'a D) -> &'a Self + MaybeSync,
+        Self: 'a,
+    {
+        let (classes, class_count) = utils::class_indices(data);
+
+        // Classes come first, so they are preferred over an equally good range of code points
+        let mut best: Option<CharDecision> = None;
+        for char_class in CharClass::ALL {
+            let mut true_counts = vec![0; class_count];
+            let mut false_counts = vec![0; class_count];
+            for ((entry, _), class) in data.iter().zip(&classes) {
+                if char_class.matches(*extract(entry)) {
+                    true_counts[*class] += 1;
+                } else {
+                    false_counts[*class] += 1;
+                }
+            }
+
+            let decision_eval = utils::evaluate_split(
+                search.builder.criterion,
+                entropy,
+                &true_counts,
+                &false_counts,
+            );
+            if best.as_ref().is_none_or(|best| &decision_eval > best.to_decision_eval()) {
+                best = Some(CharDecision::Class { decision_eval, class: char_class });
+            }
+        }
+
+        let vals = data.iter().zip(classes).map(|((d, _), class)| (*extract(d), class)).collect();
+        let ord =
+            utils::find_best_threshold(search, entropy, vals, class_count, |_lower, _upper| None);
+
+        let best = best.unwrap();
+        return if ord.decision_eval > *best.to_decision_eval() {
+            CharDecision::Ord(ord)
+        } else {
+            best
+        };
+    }
+
+    fn matches(&self, decision: &Self::Decision) -> bool {
+        return match decision {
+            CharDecision::Ord(ord) => {
+                if ord.inclusive {
+                    self <= &ord.threshold
+                } else {
+                    self < &ord.threshold
+                }
+            }
+            CharDecision::Class { class, .. } => class.matches(*self),
+        };
+    }
+}
+
+/// Support for `NonZero*` integers, with thresholds on their primitive value
+macro_rules! nonzero_implementation {
+    ($($t:ident => $primitive:ident),+) => ($(
+        impl BranchBuilder for $t {
+            type Decision = NonZeroDecision<$primitive>;
+
+            fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+                search: &FeatureSearch,
+                entropy: f64,
+                data: &'a [(D, R)],
+                extract: F,
+            ) -> Self::Decision
+            where
+                F: Fn(&'a D) -> &'a Self + MaybeSync,
+                Self: 'a,
+            {
+                let (classes, class_count) = utils::class_indices(data);
+                let vals = data.iter().zip(classes).map(|((d, _), class)| (extract(d).get(), class)).collect();
+
+                let inner_decision = utils::find_best_threshold(search, entropy, vals, class_count, |_lower, _upper| None);
+                return NonZeroDecision { inner_decision };
+            }
+
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return if decision.inner_decision.inclusive {
+                    self.get() <= decision.inner_decision.threshold
+                } else {
+                    self.get() < decision.inner_decision.threshold
+                };
+            }
+        }
+    )+)
+}
+
+nonzero_implementation!(
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroU128 => u128,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroI128 => i128,
+    NonZeroIsize => isize
+);
+
+/// Support for String
+impl BranchBuilder for bool {
+    type Decision = BoolDecision;
+
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+        search: &FeatureSearch,
+        entropy: f64,
+        data: &'a [(D, R)],
+        extract: F,
+    ) -> Self::Decision
+    where
+        F: Fn(&'a D) -> &'a Self + MaybeSync,
+        Self: 'a,
+    {
+        let (classes, class_count) = utils::class_indices(data);
+        let mut true_counts = vec![0; class_count];
+        let mut false_counts = vec![0; class_count];
+
+        for ((entry, _), class) in data.iter().zip(classes) {
+            if *extract(entry) {
+                true_counts[class] += 1;
+            } else {
+                false_counts[class] += 1;
+            }
+        }
+
+        let decision_eval =
+            utils::evaluate_split(search.builder.criterion, entropy, &true_counts, &false_counts);
+        return BoolDecision { decision_eval };
+    }
+
+    fn matches(&self, _decision: &Self::Decision) -> bool {
+        return *self;
+    }
+}
+
+/// Support for references and smart pointers, forwarding to the value they point to
+macro_rules! deref_implementation {
+    ($($t:ty),+) => ($(
+        impl<T: BranchBuilder + ?Sized> BranchBuilder for $t {
+            type Decision = DerefDecision<T::Decision>;
+
+            fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+                search: &FeatureSearch,
+                entropy: f64,
+                data: &'a [(D, R)],
+                extract: F,
+            ) -> Self::Decision
+            where
+                F: Fn(&'a D) -> &'a Self + MaybeSync,
+                Self: 'a,
+            {
+                let inner_decision = T::find_best_decision(search, entropy, data, |d| extract(d).deref());
+                return DerefDecision { inner_decision };
+            }
+
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return (**self).matches(&decision.inner_decision);
+            }
+        }
+    )+)
+}
+
+deref_implementation!(&T, Box<T>, Rc<T>, Arc<T>);
+
+/// Support for tuples, with a decision on one of the elements
+macro_rules! tuple_implementation {
+    ($name:ident, $count:literal, $($variant:ident $t:ident $index:tt),+) => {
+        impl<$($t),+> BranchBuilder for ($($t,)+)
+        where
+            $($t: BranchBuilder),+
+        {
+            type Decision = crate::$name<$($t::Decision),+>;
+
+            fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+                search: &FeatureSearch,
+                entropy: f64,
+                data: &'a [(D, R)],
+                extract: F,
+            ) -> Self::Decision
+            where
+                F: Fn(&'a D) -> &'a Self + MaybeSync,
+                Self: 'a,
+            {
+                return parallel::map($count, |i| match i {
+                    $($index => crate::$name::$variant(BranchBuilder::find_best_decision(&search.field($index), entropy, data, |d| &extract(d).$index)),)+
+                    _ => unreachable!(),
+                })
+                .into_iter()
+                .max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval()))
+                .unwrap();
+            }
+
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return match decision {
+                    $(crate::$name::$variant(inner) => self.$index.matches(inner)),+
+                };
+            }
+        }
+    };
+}
+
+tuple_implementation!(Tuple1Decision, 1, A T0 0);
+tuple_implementation!(Tuple2Decision, 2, A T0 0, B T1 1);
+tuple_implementation!(Tuple3Decision, 3, A T0 0, B T1 1, C T2 2);
+tuple_implementation!(Tuple4Decision, 4, A T0 0, B T1 1, C T2 2, D T3 3);
+tuple_implementation!(Tuple5Decision, 5, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4);
+tuple_implementation!(Tuple6Decision, 6, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5);
+tuple_implementation!(Tuple7Decision, 7, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5, G T6 6);
+tuple_implementation!(Tuple8Decision, 8, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5, G T6 6, H T7 7);
+tuple_implementation!(Tuple9Decision, 9, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5, G T6 6, H T7 7, I T8 8);
+tuple_implementation!(Tuple10Decision, 10, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5, G T6 6, H T7 7, I T8 8, J T9 9);
+tuple_implementation!(Tuple11Decision, 11, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5, G T6 6, H T7 7, I T8 8, J T9 9, K T10 10);
+tuple_implementation!(Tuple12Decision, 12, A T0 0, B T1 1, C T2 2, D T3 3, E T4 4, F T5 5, G T6 6, H T7 7, I T8 8, J T9 9, K T10 10, L T11 11);
+
+/// Support for arrays
+impl<T, const N: usize> BranchBuilder for [T; N]
+where T: BranchBuilder
+{
+    type Decision = ArrayDecision<T::Decision>;
+
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+        search: &FeatureSearch,
+        entropy: f64,
+        data: &'a [(D, R)],
+        extract: F,
+    ) -> Self::Decision
+    where
+        F: Fn(&'a D) -> &'a Self + MaybeSync,
+        Self: 'a,
+    {
+        return parallel::map(N, |i| {
+            ArrayDecision {
+                index: i,
+                inner_decision: BranchBuilder::find_best_decision(
+                    &search.field(i),
+                    entropy,
+                    data,
+                    |d| &extract(d)[i],
+                ),
+            }
+        })
+        .into_iter()
+        .max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval()))
+        .unwrap();
+    }
+
+    fn matches(&self, decision: &Self::Decision) -> bool {
+        return self[decision.index].matches(&decision.inner_decision);
+    }
+}
+
+/// Support for slices
+impl<T> BranchBuilder for [T]
+where T: BranchBuilder + MaybeSync
+{
+    type Decision = VecDecision<T::Decision>;
+
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+        search: &FeatureSearch,
+        entropy: f64,
+        data: &'a [(D, R)],
+        extract: F,
+    ) -> Self::Decision
+    where
+        F: Fn(&'a D) -> &'a Self + MaybeSync,
+        Self: 'a,
+    {
+        let (classes, class_count) = utils::class_indices(data);
+
+        // Evaluates the split of the whole data, as the inner decisions are only found on some of the values
+        let evaluate = |matches: &dyn Fn(&[T]) -> bool| {
+            let mut true_counts = vec![0; class_count];
+            let mut false_counts = vec![0; class_count];
+            for ((entry, _), class) in data.iter().zip(&classes) {
+                if matches(extract(entry)) {
+                    true_counts[*class] += 1;
+                } else {
+                    false_counts[*class] += 1;
+                }
+            }
+            return utils::evaluate_split(
+                search.builder.criterion,
+                entropy,
+                &true_counts,
+                &false_counts,
+            );
+        };
+
+        let lens =
+            data.iter().zip(&classes).map(|((d, _), class)| (extract(d).len(), *class)).collect();
+        let mut best = VecDecision::Len(utils::find_best_threshold(
+            search,
+            entropy,
+            lens,
+            class_count,
+            |_, _| None,
+        ));
+
+        let max_len = data.iter().map(|(d, _)| extract(d).len()).max().unwrap_or(0);
+        let indexed = parallel::map(max_len.min(MAX_INDEXED_ELEMENTS), |index| {
+            let values: Vec<(&T, R)> = data
+                .iter()
+                .filter_map(|(d, res)| extract(d).get(index).map(|v| (v, *res)))
+                .collect();
+            let inner_entropy = utils::entropy(&utils::to_counts(&values));
+            let inner_decision =
+                T::find_best_decision(&search.field(index), inner_entropy, &values, |v| *v);
+            let decision_eval =
+                evaluate(&|vals| vals.get(index).is_some_and(|v| v.matches(&inner_decision)));
+            return VecDecision::Index { decision_eval, index, inner_decision };
+        });
+
+        // Decisions on any value are found on all the values, each labeled with the result of its entry
+        let values: Vec<(&T, R)> =
+            data.iter().flat_map(|(d, res)| extract(d).iter().map(|v| (v, *res))).collect();
+        let any = (!values.is_empty()).then(|| {
+            let inner_entropy = utils::entropy(&utils::to_counts(&values));
+            let inner_decision =
+                T::find_best_decision(&search.any_element(), inner_entropy, &values, |v| *v);
+            let decision_eval = evaluate(&|vals| vals.iter().any(|v| v.matches(&inner_decision)));
+            return VecDecision::Any { decision_eval, inner_decision };
+        });
+
+        // Keeps the first of equally good decisions, preferring the simpler ones
+        for decision in indexed.into_iter().chain(any) {
+            if decision.to_decision_eval() > best.to_decision_eval() {
+                best = decision;
+            }
+        }
+        return best;
+    }
+
+    fn matches(&self, decision: &Self::Decision) -> bool {
+        return match decision {
+            VecDecision::Len(len) => self.len().matches(len),
+            VecDecision::Index { index, inner_decision, .. } => {
+                self.get(*index).is_some_and(|v| v.matches(inner_decision))
+            }
+            VecDecision::Any { inner_decision, .. } => {
+                self.iter().any(|v| v.matches(inner_decision))
+            }
+        };
+    }
+}
+
+/// Support for vectors, with the same decisions as slices
+impl<T> BranchBuilder for Vec<T>
+where T: BranchBuilder + MaybeSync
+{
+    type Decision = VecDecision<T::Decision>;
+
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(
+        search: &FeatureSearch,
+        entropy: f64,
+        data: &'a [(D, R)],
+        extract: F,
+    ) -> Self::Decision
+    where
+        F: Fn(&'a D) -> &'a Self + MaybeSync,
+        Self: 'a,
+    {
+        return <[T]>::find_best_decision(search, entropy, data, |d| extract(d).as_slice());
+    }
+
+    fn matches(&self, decision: &Self::Decision) -> bool {
+        return self.as_slice().matches(decision);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::hash::Hash;
+    use std::num::NonZeroU32;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::*;
+
+    /// Finds the best decision on the whole values of `data`, with the default options.
+    fn find_best_decision<T: BranchBuilder + MaybeSync, R: Copy + Eq + Hash + MaybeSync>(
+        data: &[(T, R)],
+    ) -> T::Decision {
+        let builder = TreeBuilder::default();
+        return T::find_best_decision(&FeatureSearch::new(&builder), utils::entropy(&utils::to_counts(data)), data, |v| v);
+    }
+
+    #[test]
+    fn test_bool() {
+        let data = [(true, true), (false, false)];
+        let decision = find_best_decision(&data);
+        assert!(true.matches(&decision));
+        assert_eq!(decision.to_decision_eval().max_branch_width, 1);
+        assert_eq!(decision.to_decision_eval().gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_bool_ref() {
+        let data = [(&true, true), (&false, false), (&false, false)];
+        let decision = BranchBuilder::find_best_decision(
+            &FeatureSearch::new(&TreeBuilder::default()),
+            utils::entropy(&utils::to_counts(&data)),
+            &data[..],
+            |v| *v,
+        );
+        assert!(true.matches(&decision));
+        assert_eq!(decision.to_decision_eval().max_branch_width, 2);
+        assert_eq!(decision.to_decision_eval().gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_tuple() {
+        let data = [((0, 0), true), ((0, 1), false), ((1, 0), false)];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, Tuple2Decision::B(OrdDecision { threshold: 1, .. })));
+        assert_eq!(decision.to_decision_eval().max_branch_width, 2);
+        assert_eq!(decision.to_decision_eval().gain_ratio, 0.274017542121281);
+    }
+
+    #[test]
+    fn test_string() {
+        let mut data = [
+            (String::from("Mozilla/5.0 (Windows NT 10.0)"), 1),
+            (String::from("Mozilla/5.0 (X11; Linux x86_64)"), 1),
+            (String::from("curl/8.4.0"), 2),
+            (String::from("curl/7.88.1"), 2),
+        ];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&String::from("Mozilla/5.0 (Macintosh)")), 1);
+        assert_eq!(tree.predict(&String::from("curl/8.5.0")), 2);
+
+        let data = [("a", true), ("bb", true), ("abcd", false), ("bbbbb", false)];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, StringDecision::Len(OrdDecision { threshold: 4, .. })));
+        assert!(BranchBuilder::matches(&"abc", &decision));
+    }
+
+    #[test]
+    fn test_char() {
+        let data = [('1', true), ('7', true), ('a', false), ('-', false)];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, CharDecision::Class { class: CharClass::AsciiDigit, .. }));
+        assert!('5'.matches(&decision));
+        assert!(!'x'.matches(&decision));
+
+        let data = [('p', true), ('q', true), ('u', false), ('z', false)];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, CharDecision::Ord(OrdDecision { threshold: 'u', .. })));
+        assert!('r'.matches(&decision));
+    }
+
+    #[test]
+    fn test_nonzero() {
+        let mut data = [
+            (NonZeroU32::new(1).unwrap(), 1),
+            (NonZeroU32::new(4).unwrap(), 1),
+            (NonZeroU32::new(9).unwrap(), 2),
+        ];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&NonZeroU32::new(2).unwrap()), 1);
+        assert_eq!(tree.predict(&NonZeroU32::new(10).unwrap()), 2);
+    }
+
+    #[test]
+    fn test_duration() {
+        let data = [
+            (Duration::from_millis(10), true),
+            (Duration::from_millis(20), true),
+            (Duration::from_millis(50), false),
+        ];
+        let decision = find_best_decision(&data);
+        assert_eq!(decision.threshold, Duration::from_millis(35));
+        assert!(Duration::from_millis(30).matches(&decision));
+        assert!(!Duration::from_millis(40).matches(&decision));
+    }
+
+    #[test]
+    fn test_deref() {
+        let values = [String::from("a"), String::from("b"), String::from("c")];
+        let mut data = [(&values[0], 1), (&values[1], 2), (&values[2], 1)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&&values[1]), 2);
+        assert_eq!(tree.predict(&&values[2]), 1);
+
+        let mut data = [((Box::new(1u32), Arc::new(0.5)), 1), ((Box::new(2), Arc::new(0.5)), 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&data[0].0), 1);
+        assert_eq!(tree.predict(&data[1].0), 2);
+
+        let data = [(Box::new(1u32), true), (Box::new(2), false)];
+        let decision = find_best_decision(&data);
+        assert!(Rc::new(1u32).matches(&decision));
+        assert!(!Rc::new(2u32).matches(&decision));
+    }
+
+    #[test]
+    fn test_cow() {
+        let mut data =
+            [(Cow::Borrowed("a"), 1), (Cow::Owned(String::from("b")), 2), (Cow::Borrowed("b"), 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&Cow::Owned(String::from("a"))), 1);
+        assert_eq!(tree.predict(&Cow::Borrowed("b")), 2);
+    }
+
+    #[test]
+    fn test_single_tuple() {
+        let data = [((true,), true), ((false,), false)];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, Tuple1Decision::A(BoolDecision { .. })));
+        assert!((true,).matches(&decision));
+    }
+
+    #[test]
+    fn test_large_tuple() {
+        let data = [
+            ((0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1), true),
+            ((0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2), false),
+        ];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, Tuple12Decision::L(OrdDecision { threshold: 2, .. })));
+        assert!(data[0].0.matches(&decision));
+        assert!(!data[1].0.matches(&decision));
+    }
+
+    #[test]
+    fn test_recursive_tuple() {
+        let data = [
+            (((0, 0), (true, true)), true),
+            (((0, 1), (false, false)), false),
+            (((1, 0), (true, true)), false),
+            (((1, 1), (false, false)), false),
+        ];
+        let original_entropy = utils::entropy(&utils::to_counts(&data));
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, Tuple2Decision::B(Tuple2Decision::B(BoolDecision { .. }))));
+        assert_eq!(decision.to_decision_eval().max_branch_width, 2);
+
+        assert_eq!(decision.to_decision_eval().gain_ratio, original_entropy - 0.5);
+    }
+
+    #[test]
+    fn test_list() {
+        let data = [
+            ([true, true], true),
+            ([true, false], false),
+            ([false, true], false),
+            ([false, false], true),
+        ];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, ArrayDecision { index: 1, .. }));
+        assert_eq!(decision.to_decision_eval().max_branch_width, 2);
+        assert_eq!(decision.to_decision_eval().gain_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_vec_len() {
+        let data = [
+            (vec![1, 2], true),
+            (vec![2, 1], true),
+            (vec![1, 2, 3], false),
+            (vec![3, 3, 3, 3], false),
+        ];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, VecDecision::Len(OrdDecision { threshold: 3, .. })));
+        assert_eq!(decision.to_decision_eval().gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_vec_index() {
+        let data =
+            [(vec![1, 2], true), (vec![2, 1, 9], true), (vec![1, 8, 4], false), (vec![2], false)];
+        let decision = find_best_decision(&data);
+        assert!(matches!(decision, VecDecision::Index { index: 1, .. }));
+        assert!(!vec![9].matches(&decision));
+        assert_eq!(decision.to_decision_eval().gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_vec_any() {
+        let data = [
+            (vec!["a", "b"], true),
+            (vec!["c", "d", "b"], true),
+            (vec!["a", "c", "d"], false),
+            (vec![], false),
+        ];
+        let decision = find_best_decision(&data);
+        assert!(
+            matches!(&decision, VecDecision::Any { inner_decision: StringDecision::Eq(eq), .. } if eq.vals == ["b"])
+        );
+        assert_eq!(decision.to_decision_eval().gain_ratio, 1.0);
+    }
+
+    /// Using numbers form https://sefiks.com/2018/05/13/a-step-by-step-c4-5-decision-tree-example/
+    #[test]
+    fn test_example() {
+        let data = [
+            ("Weak", false),
+            ("Strong", false),
+            ("Weak", true),
+            ("Weak", true),
+            ("Weak", true),
+            ("Strong", false),
+            ("Strong", true),
+            ("Weak", false),
+            ("Weak", true),
+            ("Weak", true),
+            ("Strong", true),
+            ("Strong", true),
+            ("Weak", true),
+            ("Strong", false),
+        ];
+        let decision = find_best_decision(&data);
+        assert!(matches!(&decision, StringDecision::Eq(eq) if eq.vals == ["Weak"]));
+        assert_eq!(decision.to_decision_eval().max_branch_width, 8);
+        assert_eq!(decision.to_decision_eval().gain_ratio, 0.048848615511520824);
+    }
+}
@@ -0,0 +1,105 @@
+/// Implements `BranchBuilder` with threshold decisions for a `Copy + PartialOrd` type.
+#[macro_export]
+macro_rules! ord_implementation {
+    ($t:ident) => {
+        $crate::ord_implementation!($t, |_lower, _upper| None);
+    };
+    ($t:ident, float) => {
+        $crate::ord_implementation!($t, |lower: $t, upper: $t| {
+            // Halving first avoids overflowing to infinity, and rounding
+            // may leave no representable value strictly between the two
+            let midpoint = lower / 2.0 + upper / 2.0;
+            return (lower < midpoint && midpoint <= upper).then_some(midpoint);
+        });
+    };
+    ($t:ident, $midpoint:expr) => {
+        impl $crate::BranchBuilder for $t {
+            type Decision = $crate::OrdDecision<Self>;
+
+            fn find_best_decision<'a, R, F, D>(
+                search: &$crate::FeatureSearch,
+                entropy: f64,
+                data: &'a [(D, R)],
+                extract: F,
+            ) -> Self::Decision
+            where
+                R: Copy + Eq + std::hash::Hash + $crate::MaybeSync,
+                F: Fn(&'a D) -> &'a Self + $crate::MaybeSync,
+                Self: 'a,
+                D: $crate::MaybeSync,
+            {
+                let (classes, class_count) = $crate::utils::class_indices(data);
+                let vals = data.iter().zip(classes).map(|((d, _), class)| (*extract(d), class)).collect();
+
+                return $crate::utils::find_best_threshold(
+                    search,
+                    entropy,
+                    vals,
+                    class_count,
+                    $midpoint,
+                );
+            }
+
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return if decision.inclusive {
+                    self <= &decision.threshold
+                } else {
+                    self < &decision.threshold
+                };
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use crate::{OrdDecision, Tree, TreeBuilder};
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum TestEnum {
+        A,
+        B,
+    }
+
+    ord_implementation!(TestEnum);
+
+    impl PartialOrd for TestEnum {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            let res = match self {
+                TestEnum::A => {
+                    match other {
+                        TestEnum::A => Ordering::Equal,
+                        TestEnum::B => Ordering::Greater,
+                    }
+                }
+                TestEnum::B => {
+                    match other {
+                        TestEnum::A => Ordering::Less,
+                        TestEnum::B => Ordering::Equal,
+                    }
+                }
+            };
+            return Some(res);
+        }
+    }
+
+    #[test]
+    fn test_enum() {
+        let mut data = [(TestEnum::A, 1), (TestEnum::B, 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&TestEnum::A), 1);
+        assert_eq!(tree.predict(&TestEnum::B), 2);
+    }
+
+    #[test]
+    fn test_float_nan() {
+        let mut data = [(1.0, 1), (f64::NAN, 2), (2.0, 1), (5.0, 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert!(matches!(tree, Tree::Branch { decision: OrdDecision { threshold: 3.5, .. }, .. }));
+        assert_eq!(tree.predict(&3.0), 1);
+        assert_eq!(tree.predict(&4.0), 2);
+        assert_eq!(tree.predict(&f64::NAN), 2);
+    }
+}
@@ -1,8 +1,9 @@
 use std::cmp::Ordering;
 
 pub struct DecisionEval {
-    pub(crate) gain_ratio: f64,
-    pub(crate) max_branch_width: usize,
+    /// Quality of the split by the `Criterion` of the builder, the gain ratio by default.
+    pub gain_ratio: f64,
+    pub max_branch_width: usize,
 }
 
 impl Eq for DecisionEval {}
@@ -11,7 +12,7 @@ impl PartialEq<Self> for DecisionEval {
     fn eq(&self, other: &Self) -> bool {
         return ((self.gain_ratio == other.gain_ratio)
             || (self.gain_ratio.is_nan() && other.gain_ratio.is_nan()))
-            && (self.max_branch_width == self.max_branch_width);
+            && (self.max_branch_width == other.max_branch_width);
     }
 }
 
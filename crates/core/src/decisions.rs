@@ -0,0 +1,269 @@
+use crate::decision_eval::DecisionEval;
+
+pub trait Decision {
+    fn to_decision_eval(&self) -> &DecisionEval;
+}
+
+pub struct BoolDecision {
+    pub decision_eval: DecisionEval,
+}
+
+impl Decision for BoolDecision {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return &self.decision_eval;
+    }
+}
+
+/// Decision on whether the value is equal to one of `vals`.
+pub struct EqDecision<T> {
+    pub decision_eval: DecisionEval,
+    pub vals: Vec<T>,
+}
+
+impl<T> Decision for EqDecision<T> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return &self.decision_eval;
+    }
+}
+
+/// Decision on a string, by equality, by a pattern taken from the training data, or by its length.
+pub enum StringDecision {
+    Eq(EqDecision<String>),
+    StartsWith { decision_eval: DecisionEval, pattern: String },
+    EndsWith { decision_eval: DecisionEval, pattern: String },
+    Contains { decision_eval: DecisionEval, pattern: String },
+    /// Decision on the length in bytes.
+    Len(OrdDecision<usize>),
+}
+
+impl StringDecision {
+    pub fn matches(&self, val: &str) -> bool {
+        return match self {
+            StringDecision::Eq(eq) => eq.vals.iter().any(|eq_val| eq_val == val),
+            StringDecision::StartsWith { pattern, .. } => val.starts_with(pattern.as_str()),
+            StringDecision::EndsWith { pattern, .. } => val.ends_with(pattern.as_str()),
+            StringDecision::Contains { pattern, .. } => val.contains(pattern.as_str()),
+            StringDecision::Len(len) => {
+                if len.inclusive {
+                    val.len() <= len.threshold
+                } else {
+                    val.len() < len.threshold
+                }
+            }
+        };
+    }
+}
+
+impl Decision for StringDecision {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return match self {
+            StringDecision::Eq(eq) => eq.to_decision_eval(),
+            StringDecision::StartsWith { decision_eval, .. } => decision_eval,
+            StringDecision::EndsWith { decision_eval, .. } => decision_eval,
+            StringDecision::Contains { decision_eval, .. } => decision_eval,
+            StringDecision::Len(len) => len.to_decision_eval(),
+        };
+    }
+}
+
+pub struct OrdDecision<T> {
+    pub decision_eval: DecisionEval,
+    pub threshold: T,
+    /// Whether values equal to the threshold also match (`<=` instead of `<`).
+    pub inclusive: bool,
+}
+
+impl<T> Decision for OrdDecision<T> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return &self.decision_eval;
+    }
+}
+
+/// Decision on a linear combination of numeric values, `weights · values < threshold`.
+pub struct ObliqueDecision<const N: usize> {
+    pub decision_eval: DecisionEval,
+    pub weights: [f64; N],
+    pub threshold: f64,
+}
+
+impl<const N: usize> ObliqueDecision<N> {
+    /// Checks whether the values are below the hyperplane, skipping values with a weight of zero.
+    pub fn matches(&self, values: &[f64; N]) -> bool {
+        let mut sum = 0.0;
+        for (weight, value) in self.weights.iter().zip(values) {
+            if *weight != 0.0 {
+                sum += weight * value;
+            }
+        }
+        return sum < self.threshold;
+    }
+}
+
+impl<const N: usize> Decision for ObliqueDecision<N> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return &self.decision_eval;
+    }
+}
+
+/// Defines the decision of a tuple, with a variant for the decision on each of its elements.
+macro_rules! tuple_decision {
+    ($name:ident, $($t:ident),+) => {
+        pub enum $name<$($t),+> {
+            $($t($t)),+
+        }
+
+        impl<$($t: Decision),+> Decision for $name<$($t),+> {
+            fn to_decision_eval(&self) -> &DecisionEval {
+                return match self {
+                    $($name::$t(inner) => inner.to_decision_eval()),+
+                };
+            }
+        }
+    };
+}
+
+tuple_decision!(Tuple1Decision, A);
+tuple_decision!(Tuple2Decision, A, B);
+tuple_decision!(Tuple3Decision, A, B, C);
+tuple_decision!(Tuple4Decision, A, B, C, D);
+tuple_decision!(Tuple5Decision, A, B, C, D, E);
+tuple_decision!(Tuple6Decision, A, B, C, D, E, F);
+tuple_decision!(Tuple7Decision, A, B, C, D, E, F, G);
+tuple_decision!(Tuple8Decision, A, B, C, D, E, F, G, H);
+tuple_decision!(Tuple9Decision, A, B, C, D, E, F, G, H, I);
+tuple_decision!(Tuple10Decision, A, B, C, D, E, F, G, H, I, J);
+tuple_decision!(Tuple11Decision, A, B, C, D, E, F, G, H, I, J, K);
+tuple_decision!(Tuple12Decision, A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Decision on a variable number of values, e.g. a `Vec`.
+pub enum VecDecision<T> {
+    /// Decision on the number of values.
+    Len(OrdDecision<usize>),
+    /// The value at `index` exists and matches the inner decision.
+    Index { decision_eval: DecisionEval, index: usize, inner_decision: T },
+    /// Any of the values matches the inner decision.
+    Any { decision_eval: DecisionEval, inner_decision: T },
+}
+
+impl<T> Decision for VecDecision<T> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return match self {
+            VecDecision::Len(len) => len.to_decision_eval(),
+            VecDecision::Index { decision_eval, .. } => decision_eval,
+            VecDecision::Any { decision_eval, .. } => decision_eval,
+        };
+    }
+}
+
+/// Classes of characters, each checked with the `char` method of the same name.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CharClass {
+    AsciiDigit,
+    AsciiHexdigit,
+    AsciiPunctuation,
+    Ascii,
+    Alphabetic,
+    Alphanumeric,
+    Numeric,
+    Whitespace,
+    Uppercase,
+    Lowercase,
+    Control,
+}
+
+impl CharClass {
+    pub const ALL: [CharClass; 11] = [
+        CharClass::AsciiDigit,
+        CharClass::AsciiHexdigit,
+        CharClass::AsciiPunctuation,
+        CharClass::Ascii,
+        CharClass::Alphabetic,
+        CharClass::Alphanumeric,
+        CharClass::Numeric,
+        CharClass::Whitespace,
+        CharClass::Uppercase,
+        CharClass::Lowercase,
+        CharClass::Control,
+    ];
+
+    pub fn matches(self, c: char) -> bool {
+        return match self {
+            CharClass::AsciiDigit => c.is_ascii_digit(),
+            CharClass::AsciiHexdigit => c.is_ascii_hexdigit(),
+            CharClass::AsciiPunctuation => c.is_ascii_punctuation(),
+            CharClass::Ascii => c.is_ascii(),
+            CharClass::Alphabetic => c.is_alphabetic(),
+            CharClass::Alphanumeric => c.is_alphanumeric(),
+            CharClass::Numeric => c.is_numeric(),
+            CharClass::Whitespace => c.is_whitespace(),
+            CharClass::Uppercase => c.is_uppercase(),
+            CharClass::Lowercase => c.is_lowercase(),
+            CharClass::Control => c.is_control(),
+        };
+    }
+
+    /// Name of the `char` method checking the class.
+    pub fn method_name(self) -> &'static str {
+        return match self {
+            CharClass::AsciiDigit => "is_ascii_digit",
+            CharClass::AsciiHexdigit => "is_ascii_hexdigit",
+            CharClass::AsciiPunctuation => "is_ascii_punctuation",
+            CharClass::Ascii => "is_ascii",
+            CharClass::Alphabetic => "is_alphabetic",
+            CharClass::Alphanumeric => "is_alphanumeric",
+            CharClass::Numeric => "is_numeric",
+            CharClass::Whitespace => "is_whitespace",
+            CharClass::Uppercase => "is_uppercase",
+            CharClass::Lowercase => "is_lowercase",
+            CharClass::Control => "is_control",
+        };
+    }
+}
+
+/// Decision on a `char`, either by its code point or by its class.
+pub enum CharDecision {
+    Ord(OrdDecision<char>),
+    Class { decision_eval: DecisionEval, class: CharClass },
+}
+
+impl Decision for CharDecision {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return match self {
+            CharDecision::Ord(ord) => ord.to_decision_eval(),
+            CharDecision::Class { decision_eval, .. } => decision_eval,
+        };
+    }
+}
+
+/// Decision on the value of a `NonZero*` integer, as the primitive integer returned by `get()`.
+pub struct NonZeroDecision<T> {
+    pub inner_decision: OrdDecision<T>,
+}
+
+impl<T> Decision for NonZeroDecision<T> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return self.inner_decision.to_decision_eval();
+    }
+}
+
+/// Decision on the value behind a reference or a smart pointer.
+pub struct DerefDecision<T> {
+    pub inner_decision: T,
+}
+
+impl<T: Decision> Decision for DerefDecision<T> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return self.inner_decision.to_decision_eval();
+    }
+}
+
+pub struct ArrayDecision<T> {
+    pub index: usize,
+    pub inner_decision: T,
+}
+
+impl<T: Decision> Decision for ArrayDecision<T> {
+    fn to_decision_eval(&self) -> &DecisionEval {
+        return self.inner_decision.to_decision_eval();
+    }
+}
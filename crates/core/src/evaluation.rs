@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+
+use crate::{BranchBuilder, Tree};
+
+/// Classification metrics of a tree on a set of labelled values.
+pub struct Evaluation<R> {
+    /// Share of the values predicted correctly, 0 without values.
+    pub accuracy: f64,
+    /// Number of values for each actual result and the result predicted for them.
+    pub confusion_matrix: HashMap<R, HashMap<R, usize>>,
+    /// Metrics of each result, in order of their first appearance as an actual or predicted result.
+    pub classes: Vec<ClassMetrics<R>>,
+}
+
+/// Metrics of a single result, treating it as the positive class.
+pub struct ClassMetrics<R> {
+    pub class: R,
+    /// Share of the values predicted as this result that actually have it, 0 if it is never predicted.
+    pub precision: f64,
+    /// Share of the values with this result that are predicted correctly, 0 if it never occurs.
+    pub recall: f64,
+    /// Harmonic mean of the precision and the recall.
+    pub f1: f64,
+    /// Number of values with this result.
+    pub support: usize,
+}
+
+/// Regression metrics of a tree on a set of labelled values, with results converted to `f64`.
+/// All the metrics are 0 without values.
+pub struct RegressionEvaluation {
+    /// Mean absolute error.
+    pub mae: f64,
+    /// Root mean squared error.
+    pub rmse: f64,
+    /// Coefficient of determination, 1 for perfect predictions. When all values have the same result
+    /// it is 1 if all predictions are correct and 0 otherwise.
+    pub r2: f64,
+}
+
+/// Evaluates the predictions of `tree` on `data` as a classification.
+pub fn evaluate<T: BranchBuilder, R: Copy + Eq + Hash>(tree: &Tree<T, R>, data: &[(T, R)]) -> Evaluation<R> {
+    let mut confusion_matrix: HashMap<R, HashMap<R, usize>> = HashMap::new();
+    let mut order = vec![];
+    let mut correct = 0;
+
+    for (val, actual) in data {
+        let predicted = tree.predict(val);
+        for result in [*actual, predicted] {
+            if !order.contains(&result) {
+                order.push(result);
+            }
+        }
+        if predicted == *actual {
+            correct += 1;
+        }
+        *confusion_matrix.entry(*actual).or_default().entry(predicted).or_default() += 1;
+    }
+
+    let count = |actual: &R, predicted: &R| {
+        return confusion_matrix.get(actual).and_then(|row| row.get(predicted)).copied().unwrap_or(0);
+    };
+
+    let classes = order
+        .iter()
+        .map(|class| {
+            let true_positives = count(class, class);
+            let support: usize = confusion_matrix.get(class).map_or(0, |row| row.values().sum());
+            let predicted: usize = order.iter().map(|actual| count(actual, class)).sum();
+
+            let precision = ratio(true_positives, predicted);
+            let recall = ratio(true_positives, support);
+            let f1 = if precision + recall == 0.0 { 0.0 } else { 2.0 * precision * recall / (precision + recall) };
+            return ClassMetrics { class: *class, precision, recall, f1, support };
+        })
+        .collect();
+
+    let accuracy = ratio(correct, data.len());
+    return Evaluation { accuracy, confusion_matrix, classes };
+}
+
+/// Numeric results of regressions, converted to `f64` with `as`, so large integers may lose precision.
+pub trait ToF64: Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! to_f64_implementation {
+    ($($t:ty),+) => ($(
+        impl ToF64 for $t {
+            fn to_f64(self) -> f64 {
+                return self as f64;
+            }
+        }
+    )+)
+}
+
+to_f64_implementation!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// Evaluates the predictions of `tree` on `data` as a regression.
+pub fn evaluate_regression<T: BranchBuilder, R: ToF64>(tree: &Tree<T, R>, data: &[(T, R)]) -> RegressionEvaluation {
+    if data.is_empty() {
+        return RegressionEvaluation { mae: 0.0, rmse: 0.0, r2: 0.0 };
+    }
+
+    let count = data.len() as f64;
+    let mean = data.iter().map(|(_, actual)| actual.to_f64()).sum::<f64>() / count;
+
+    let mut absolute_error = 0.0;
+    let mut squared_error = 0.0;
+    let mut squared_deviation = 0.0;
+    for (val, actual) in data {
+        let actual = actual.to_f64();
+        let error = tree.predict(val).to_f64() - actual;
+        absolute_error += error.abs();
+        squared_error += error * error;
+        squared_deviation += (actual - mean) * (actual - mean);
+    }
+
+    let r2 = if squared_deviation != 0.0 {
+        1.0 - squared_error / squared_deviation
+    } else if squared_error == 0.0 {
+        1.0
+    } else {
+        0.0
+    };
+    return RegressionEvaluation { mae: absolute_error / count, rmse: (squared_error / count).sqrt(), r2 };
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    return if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 };
+}
+
+/// Writes rows of cells as a table, left aligning the first column and right aligning the others.
+fn write_table(f: &mut Formatter<'_>, rows: &[Vec<String>]) -> fmt::Result {
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..column_count)
+        .map(|column| rows.iter().filter_map(|row| row.get(column)).map(|cell| cell.chars().count()).max().unwrap_or(0))
+        .collect();
+
+    for row in rows {
+        let mut line = String::new();
+        for (column, cell) in row.iter().enumerate() {
+            if column == 0 {
+                line += &format!("{cell:<width$}", width = widths[column]);
+            } else {
+                line += &format!(" | {cell:>width$}", width = widths[column]);
+            }
+        }
+        writeln!(f, "{}", line.trim_end())?;
+    }
+    return Ok(());
+}
+
+impl<R: Debug + Eq + Hash> Display for Evaluation<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let support: usize = self.classes.iter().map(|class| class.support).sum();
+        writeln!(f, "accuracy: {:.3} ({support} values)", self.accuracy)?;
+        writeln!(f)?;
+
+        let labels: Vec<String> = self.classes.iter().map(|class| format!("{:?}", class.class)).collect();
+        let mut rows = vec![std::iter::once(String::from("actual \\ predicted")).chain(labels.iter().cloned()).collect()];
+        for (actual, label) in self.classes.iter().zip(&labels) {
+            let row = self.confusion_matrix.get(&actual.class);
+            let counts = self.classes.iter().map(|predicted| {
+                return row.and_then(|row| row.get(&predicted.class)).copied().unwrap_or(0).to_string();
+            });
+            rows.push(std::iter::once(label.clone()).chain(counts).collect());
+        }
+        write_table(f, &rows)?;
+        writeln!(f)?;
+
+        let mut rows = vec![["class", "precision", "recall", "f1", "support"].map(String::from).to_vec()];
+        for (class, label) in self.classes.iter().zip(labels) {
+            rows.push(vec![
+                label,
+                format!("{:.3}", class.precision),
+                format!("{:.3}", class.recall),
+                format!("{:.3}", class.f1),
+                class.support.to_string(),
+            ]);
+        }
+        return write_table(f, &rows);
+    }
+}
+
+impl Display for RegressionEvaluation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rows = [
+            vec![String::from("mae"), format!("{:.3}", self.mae)],
+            vec![String::from("rmse"), format!("{:.3}", self.rmse)],
+            vec![String::from("r2"), format!("{:.3}", self.r2)],
+        ];
+        return write_table(f, &rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DecisionEval, OrdDecision, TreeBuilder};
+
+    #[test]
+    fn test_evaluate() {
+        let leaf = |result| Box::new(Tree::Leaf { result, counts: vec![] });
+        let decision_eval = DecisionEval { gain_ratio: 1.0, max_branch_width: 1 };
+        let decision = OrdDecision { decision_eval, threshold: 2, inclusive: false };
+        let tree: Tree<i32, char> = Tree::Branch { decision, true_branch: leaf('a'), false_branch: leaf('b') };
+        let evaluation = evaluate(&tree, &[(1, 'a'), (1, 'b'), (2, 'b'), (3, 'c')]);
+
+        assert_eq!(evaluation.accuracy, 0.5);
+        assert_eq!(evaluation.confusion_matrix[&'b'][&'a'], 1);
+        assert_eq!(evaluation.confusion_matrix[&'c'][&'b'], 1);
+
+        let classes: Vec<char> = evaluation.classes.iter().map(|class| class.class).collect();
+        assert_eq!(classes, ['a', 'b', 'c']);
+        let b = &evaluation.classes[1];
+        assert_eq!((b.precision, b.recall, b.support), (0.5, 0.5, 2));
+        let c = &evaluation.classes[2];
+        assert_eq!((c.precision, c.recall, c.f1, c.support), (0.0, 0.0, 0.0, 1));
+    }
+
+    #[test]
+    fn test_table() {
+        let mut data = [(1, true), (2, false), (3, false)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let evaluation = evaluate(&tree, &[(1, true), (2, false), (3, true)]);
+        let expected = "\
+accuracy: 0.667 (3 values)
+
+actual \\ predicted | true | false
+true               |    1 |     1
+false              |    0 |     1
+
+class | precision | recall |    f1 | support
+true  |     1.000 |  0.500 | 0.667 |       2
+false |     0.500 |  1.000 | 0.667 |       1
+";
+        assert_eq!(evaluation.to_string(), expected);
+    }
+
+    #[test]
+    fn test_regression() {
+        let mut data = [(1, 10), (2, 20), (3, 30)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let evaluation = evaluate_regression(&tree, &[(1, 10), (2, 20), (3, 30), (4, 40)]);
+        assert_eq!(evaluation.mae, 2.5);
+        assert_eq!(evaluation.rmse, 5.0);
+        assert_eq!(evaluation.r2, 0.8);
+        assert_eq!(evaluation.to_string(), "mae  | 2.500\nrmse | 5.000\nr2   | 0.800\n");
+
+        let mut data = [(1, 10u64), (2, 20)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(evaluate_regression(&tree, &data).mae, 0.0);
+        assert_eq!(evaluate_regression(&tree, &[]).mae, 0.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut data = [(1, true), (2, false)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let evaluation = evaluate(&tree, &[]);
+        assert_eq!(evaluation.accuracy, 0.0);
+        assert!(evaluation.classes.is_empty());
+    }
+}
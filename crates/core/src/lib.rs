@@ -0,0 +1,21 @@
+mod branch_builder;
+mod decision_eval;
+mod decisions;
+mod evaluation;
+pub mod parallel;
+mod rules;
+mod tree;
+mod tree_builder;
+#[doc(hidden)]
+pub mod utils;
+mod validation;
+
+pub use branch_builder::implementations::*;
+pub use decision_eval::*;
+pub use decisions::*;
+pub use evaluation::*;
+pub use parallel::{MaybeSend, MaybeSync};
+pub use rules::*;
+pub use tree::*;
+pub use tree_builder::*;
+pub use validation::*;
@@ -0,0 +1,79 @@
+//! Helpers running independent parts of the tree building in parallel
+//! when the `rayon` feature is enabled, and sequentially otherwise.
+//!
+//! Results are always returned in the same order as the sequential version,
+//! so the built tree does not depend on the number of threads.
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// `Send` when the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSend: Send {}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> MaybeSend for T {}
+
+/// `Send` when the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSend {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSend for T {}
+
+/// `Sync` when the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSync: Sync {}
+
+#[cfg(feature = "rayon")]
+impl<T: Sync> MaybeSync for T {}
+
+/// `Sync` when the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSync for T {}
+
+/// Runs both closures, potentially in parallel, and returns their results.
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + MaybeSend,
+    B: FnOnce() -> RB + MaybeSend,
+    RA: MaybeSend,
+    RB: MaybeSend,
+{
+    #[cfg(feature = "rayon")]
+    return rayon::join(a, b);
+
+    #[cfg(not(feature = "rayon"))]
+    return (a(), b());
+}
+
+/// Maps indices `0..n`, potentially in parallel, keeping the results in order.
+pub fn map<T, F>(n: usize, f: F) -> Vec<T>
+where
+    F: Fn(usize) -> T + MaybeSend + MaybeSync,
+    T: MaybeSend,
+{
+    #[cfg(feature = "rayon")]
+    return (0..n).into_par_iter().map(f).collect();
+
+    #[cfg(not(feature = "rayon"))]
+    return (0..n).map(f).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        assert_eq!(join(|| 1, || 2), (1, 2));
+    }
+
+    #[test]
+    fn test_map() {
+        assert_eq!(map(5, |i| i * 2), vec![0, 2, 4, 6, 8]);
+    }
+}
@@ -0,0 +1,226 @@
+use std::hash::Hash;
+
+use crate::{utils, BranchBuilder, Tree};
+
+/// IF-THEN rule predicting `result` for the values matching all of its conditions.
+pub struct Rule<'a, T: BranchBuilder, R> {
+    /// Decisions of the rule, with whether values have to match them.
+    pub conditions: Vec<(&'a T::Decision, bool)>,
+    pub result: R,
+    /// Number of training values the rule applies to.
+    pub support: usize,
+    /// Share of the values the rule applies to that have its result, 0 without values.
+    pub confidence: f64,
+}
+
+impl<'a, T: BranchBuilder, R> Rule<'a, T, R> {
+    /// Returns whether the value matches all conditions of the rule.
+    pub fn matches(&self, val: &T) -> bool {
+        return matches_all(&self.conditions, val);
+    }
+}
+
+fn matches_all<T: BranchBuilder>(conditions: &[(&T::Decision, bool)], val: &T) -> bool {
+    return conditions.iter().all(|(decision, matched)| val.matches(decision) == *matched);
+}
+
+impl<T: BranchBuilder, R: Copy + Eq> Tree<T, R> {
+    /// Returns one rule for each leaf, with the decisions on the path to it as conditions,
+    /// and the support and confidence of the leaf counts. The rules never overlap.
+    pub fn rules(&self) -> Vec<Rule<'_, T, R>> {
+        let mut rules = vec![];
+        add_rules(self, &mut vec![], &mut rules);
+        return rules;
+    }
+}
+
+fn add_rules<'a, T: BranchBuilder, R: Copy + Eq>(
+    tree: &'a Tree<T, R>,
+    conditions: &mut Vec<(&'a T::Decision, bool)>,
+    rules: &mut Vec<Rule<'a, T, R>>,
+) {
+    match tree {
+        Tree::Leaf { result, counts } => {
+            let support = counts.iter().map(|(_, count)| count).sum();
+            let correct = counts.iter().find(|(r, _)| r == result).map_or(0, |(_, count)| *count);
+            let confidence = ratio(correct, support);
+            rules.push(Rule { conditions: conditions.clone(), result: *result, support, confidence });
+        }
+        Tree::Branch { decision, true_branch, false_branch } => {
+            conditions.push((decision, true));
+            add_rules(true_branch, conditions, rules);
+            conditions.pop();
+            conditions.push((decision, false));
+            add_rules(false_branch, conditions, rules);
+            conditions.pop();
+        }
+    }
+}
+
+/// Simplifies rules on their training data as in C4.5, with the given confidence factor.
+///
+/// Conditions are dropped one at a time while that does not increase the pessimistic error rate of the rule.
+/// Rules then covering only values covered by another rule with the same result are merged into it.
+/// The remaining rules may overlap: they are ordered by decreasing confidence and the first matching rule applies.
+/// A last rule without conditions predicts the most common result of all the training data, so every value
+/// matches a rule. Support and confidence are computed for the values each rule applies to in that order.
+pub fn simplify_rules<'a, T: BranchBuilder, R: Copy + Eq + Hash>(
+    rules: Vec<Rule<'a, T, R>>,
+    data: &[(T, R)],
+    confidence: f64,
+) -> Vec<Rule<'a, T, R>> {
+    let mut simplified: Vec<(Rule<T, R>, Vec<usize>)> = vec![];
+    for mut rule in rules {
+        loop {
+            let current = error_rate(&rule.conditions, rule.result, data, confidence);
+            let mut best: Option<(usize, f64)> = None;
+            for i in 0..rule.conditions.len() {
+                let mut conditions = rule.conditions.clone();
+                conditions.remove(i);
+                let rate = error_rate(&conditions, rule.result, data, confidence);
+                if best.is_none_or(|(_, best_rate)| rate < best_rate) {
+                    best = Some((i, rate));
+                }
+            }
+            match best {
+                Some((i, rate)) if rate <= current => rule.conditions.remove(i),
+                _ => break,
+            };
+        }
+
+        let covered = covered(&rule.conditions, data);
+        let is_subset = |a: &[usize], b: &[usize]| a.iter().all(|i| b.binary_search(i).is_ok());
+        if simplified.iter().any(|(other, other_covered)| other.result == rule.result && is_subset(&covered, other_covered)) {
+            continue;
+        }
+        simplified.retain(|(other, other_covered)| other.result != rule.result || !is_subset(other_covered, &covered));
+
+        let correct = covered.iter().filter(|i| data[**i].1 == rule.result).count();
+        rule.confidence = ratio(correct, covered.len());
+        simplified.push((rule, covered));
+    }
+
+    let mut rules: Vec<Rule<T, R>> = simplified.into_iter().map(|(rule, _)| rule).collect();
+    rules.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+    // Values are counted for the first rule matching them only, rules not applying to any value are dropped
+    let mut remaining: Vec<&(T, R)> = data.iter().collect();
+    rules.retain_mut(|rule| {
+        let applying: Vec<&(T, R)>;
+        (applying, remaining) = remaining.iter().partition(|(val, _)| rule.matches(val));
+        let correct = applying.iter().filter(|(_, result)| *result == rule.result).count();
+        rule.support = applying.len();
+        rule.confidence = ratio(correct, applying.len());
+        return rule.support > 0;
+    });
+
+    // The default rule predicts the most common result of all values, and applies to the values left by the other rules
+    let results: Vec<((), R)> = remaining.iter().map(|(_, result)| ((), *result)).collect();
+    let counts = utils::to_counts(data);
+    if let Some(&(result, _)) = counts.iter().reduce(|best, count| if count.1 > best.1 { count } else { best }) {
+        let correct = results.iter().filter(|(_, r)| *r == result).count();
+        rules.push(Rule { conditions: vec![], result, support: results.len(), confidence: ratio(correct, results.len()) });
+    }
+    return rules;
+}
+
+/// Indices of the values matching all conditions, in increasing order.
+fn covered<T: BranchBuilder, R>(conditions: &[(&T::Decision, bool)], data: &[(T, R)]) -> Vec<usize> {
+    return (0..data.len()).filter(|i| matches_all(conditions, &data[*i].0)).collect();
+}
+
+/// Pessimistic error rate of a rule with the given conditions predicting `result`, 1 if it covers no values.
+fn error_rate<T: BranchBuilder, R: Eq>(conditions: &[(&T::Decision, bool)], result: R, data: &[(T, R)], confidence: f64) -> f64 {
+    let covered = covered(conditions, data);
+    if covered.is_empty() {
+        return 1.0;
+    }
+    let errors = covered.iter().filter(|i| data[**i].1 != result).count();
+    return utils::pessimistic_errors(covered.len(), errors, confidence) / covered.len() as f64;
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    return if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoolDecision, DecisionEval, OrdDecision, Tuple2Decision};
+
+    /// Tree deciding on `a < 5` and then on `b`, with the results of its leaves from left to right.
+    fn tree(results: [bool; 4]) -> Tree<(u32, bool), bool> {
+        let decision_eval = || DecisionEval { gain_ratio: 1.0, max_branch_width: 1 };
+        let leaf = |result, count| Box::new(Tree::Leaf { result, counts: vec![(result, count)] });
+        let b = |true_result, false_result| {
+            let decision = Tuple2Decision::B(BoolDecision { decision_eval: decision_eval() });
+            return Box::new(Tree::Branch { decision, true_branch: leaf(true_result, 10), false_branch: leaf(false_result, 10) });
+        };
+        let decision = Tuple2Decision::A(OrdDecision { decision_eval: decision_eval(), threshold: 5, inclusive: false });
+        return Tree::Branch { decision, true_branch: b(results[0], results[1]), false_branch: b(results[2], results[3]) };
+    }
+
+    fn data() -> Vec<((u32, bool), bool)> {
+        return (0..40).map(|i| ((i % 10, i % 4 < 2), i % 4 < 2)).collect();
+    }
+
+    #[test]
+    fn test_rules() {
+        // Only `b` matters
+        let tree = tree([true, false, true, false]);
+        let rules = tree.rules();
+        assert_eq!(rules.len(), 4);
+        let outcomes: Vec<Vec<bool>> = rules.iter().map(|rule| rule.conditions.iter().map(|(_, m)| *m).collect()).collect();
+        assert_eq!(outcomes, [[true, true], [true, false], [false, true], [false, false]]);
+        assert!(rules.iter().all(|rule| rule.support == 10 && rule.confidence == 1.0));
+        for (val, result) in data() {
+            let matching: Vec<&Rule<_, _>> = rules.iter().filter(|rule| rule.matches(&val)).collect();
+            assert_eq!(matching.len(), 1);
+            assert_eq!(matching[0].result, result);
+        }
+    }
+
+    #[test]
+    fn test_simplify_rules() {
+        let tree = tree([true, false, true, false]);
+        let data = data();
+        let rules = simplify_rules(tree.rules(), &data, 0.25);
+        assert_eq!(rules.len(), 3);
+
+        // Only the condition on `b` is left, the rules of the other branch of `a < 5` are merged into them
+        assert_eq!(rules[0].conditions.len(), 1);
+        assert!(matches!(rules[0].conditions[0], (Tuple2Decision::B(_), true)));
+        assert_eq!((rules[0].result, rules[0].support, rules[0].confidence), (true, 20, 1.0));
+        assert!(matches!(rules[1].conditions[0], (Tuple2Decision::B(_), false)));
+        assert_eq!((rules[1].result, rules[1].support), (false, 20));
+
+        // The default rule is kept even though it applies to no training value
+        assert!(rules[2].conditions.is_empty());
+        assert_eq!((rules[2].result, rules[2].support, rules[2].confidence), (true, 0, 0.0));
+    }
+
+    #[test]
+    fn test_default_rule() {
+        // Only the rule of the first leaf is given, the other values falling to the default rule
+        let data: Vec<((u32, bool), bool)> = (0..40).map(|i| ((i % 10, i % 4 < 2), i % 10 < 5 && i % 4 < 2)).collect();
+        let tree = tree([true, false, false, false]);
+        let rules = simplify_rules(tree.rules().into_iter().take(1).collect(), &data, 0.25);
+        assert_eq!(rules.len(), 2);
+        assert!(rules[1].conditions.is_empty());
+        assert_eq!((rules[1].result, rules[1].support, rules[1].confidence), (false, 30, 1.0));
+    }
+
+    #[test]
+    fn test_simplify_keeps_conditions() {
+        // Both conditions are needed
+        let data: Vec<((u32, bool), bool)> = (0..40).map(|i| ((i % 10, i % 4 < 2), i % 10 < 5 && i % 4 < 2)).collect();
+        let tree = tree([true, false, false, false]);
+        let rules = simplify_rules(tree.rules(), &data, 0.25);
+        assert_eq!(rules.len(), 4);
+        let first = rules.iter().find(|rule| rule.result).unwrap();
+        assert_eq!(first.conditions.len(), 2);
+        for (val, result) in &data {
+            assert_eq!(rules.iter().find(|rule| rule.matches(val)).unwrap().result, *result);
+        }
+    }
+}
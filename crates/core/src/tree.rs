@@ -0,0 +1,83 @@
+use crate::BranchBuilder;
+
+/// Decision tree created by the `TreeBuilder`.
+pub enum Tree<T: BranchBuilder, R> {
+    /// Final node with the predicted result and counts of training results that reached it,
+    /// ordered by the `TieBreak` used for training.
+    Leaf { result: R, counts: Vec<(R, usize)> },
+    /// Node sending values matching the decision to `true_branch` and the rest to `false_branch`.
+    Branch { decision: T::Decision, true_branch: Box<Tree<T, R>>, false_branch: Box<Tree<T, R>> },
+}
+
+/// Path taken by a value through a tree, returned by `Tree::explain`.
+pub struct Explanation<'a, T: BranchBuilder, R> {
+    /// Decisions from the root to the leaf, with whether the value matched each of them.
+    pub path: Vec<(&'a T::Decision, bool)>,
+    /// Result of the reached leaf.
+    pub result: R,
+    /// Counts of the training results that reached the leaf.
+    pub counts: &'a [(R, usize)],
+}
+
+impl<T: BranchBuilder, R: Copy> Tree<T, R> {
+    /// Returns the result of the leaf reached by the given value.
+    pub fn predict(&self, val: &T) -> R {
+        return match self {
+            Tree::Leaf { result, .. } => *result,
+            Tree::Branch { decision, true_branch, false_branch } => {
+                if val.matches(decision) {
+                    true_branch.predict(val)
+                } else {
+                    false_branch.predict(val)
+                }
+            }
+        };
+    }
+
+    /// Returns the decisions taken for the given value and the leaf it reaches.
+    pub fn explain(&self, val: &T) -> Explanation<'_, T, R> {
+        let mut path = vec![];
+        let mut tree = self;
+        loop {
+            match tree {
+                Tree::Leaf { result, counts } => return Explanation { path, result: *result, counts },
+                Tree::Branch { decision, true_branch, false_branch } => {
+                    let matched = val.matches(decision);
+                    path.push((decision, matched));
+                    tree = if matched { true_branch } else { false_branch };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TreeBuilder;
+
+    #[test]
+    fn test_predict() {
+        let mut data = [((0, 0), 1), ((0, 1), 2), ((1, 0), 2), ((1, 1), 1)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert_eq!(tree.predict(&(0, 0)), 1);
+        assert_eq!(tree.predict(&(0, 1)), 2);
+        assert_eq!(tree.predict(&(1, 0)), 2);
+        assert_eq!(tree.predict(&(1, 1)), 1);
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut data = [((0, 0), 1), ((0, 1), 2), ((1, 0), 2), ((1, 1), 1), ((1, 1), 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        for (val, _) in &data {
+            let explanation = tree.explain(val);
+            assert_eq!(explanation.result, tree.predict(val));
+            assert_eq!(explanation.path.len(), 2);
+        }
+
+        let explanation = tree.explain(&(1, 1));
+        let outcomes: Vec<bool> = explanation.path.iter().map(|(_, matched)| *matched).collect();
+        assert_eq!(outcomes, [false, false]);
+        assert_eq!(explanation.counts, [(1, 1), (2, 1)]);
+    }
+}
@@ -0,0 +1,583 @@
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::sync::Mutex;
+
+use crate::{parallel, utils, BranchBuilder, MaybeSend, MaybeSync, Tree};
+
+#[derive(Clone, Debug)]
+pub struct TreeBuilder {
+    /// Maximum number of decisions on the path to any leaf.
+    pub max_depth: usize,
+    pub threshold_placement: ThresholdPlacement,
+    /// Searches the thresholds of ordered values between at most this many quantile bins
+    /// instead of between every distinct value, trading a little accuracy for faster training.
+    /// The bin edges of each feature are computed once per build, on the values of the root node.
+    pub max_bins: Option<usize>,
+    /// Branches with fewer values than this become leaves.
+    pub min_samples_split: usize,
+    /// Prunes subtrees whose pessimistic error estimate is not better than that of a leaf, as in C4.5.
+    /// Lower confidence factors prune more, C4.5 uses 0.25 by default. Must be between 0 and 1.
+    pub pruning_confidence: Option<f64>,
+    /// Measure of the quality of the splits, the best split being chosen at every node.
+    pub criterion: Criterion,
+}
+
+/// Measure of the quality of a split, stored in `DecisionEval::gain_ratio` whichever is used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Criterion {
+    /// Information gain divided by the split information, as in C4.5,
+    /// which avoids unbalanced splits taking only a few values apart.
+    GainRatio,
+    /// Decrease of the entropy, as in ID3.
+    InformationGain,
+    /// Decrease of the Gini impurity, as in CART.
+    Gini,
+}
+
+/// Where the threshold of an ordered split is placed between the largest value
+/// of the true branch (`lower`) and the smallest value of the false branch (`upper`).
+///
+/// NaN values are never used as thresholds. They are sorted after all other values,
+/// so they are always in the false branch, during both training and prediction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThresholdPlacement {
+    /// `val <= lower`
+    Lower,
+    /// `val < upper`
+    Upper,
+    /// `val < (lower + upper) / 2` for floating point types, `Upper` for the other types
+    Midpoint,
+}
+
+/// Rule choosing the result of a leaf between results that are equally common in it.
+/// The counts of the leaves are ordered by the same rule, so trees and generated code
+/// only depend on the training data.
+#[derive(Clone, Debug)]
+pub enum TieBreak<R> {
+    /// The result appearing first in the training data.
+    FirstSeen,
+    /// The smallest result according to the ordering, see `TieBreak::smallest`.
+    Order(fn(&R, &R) -> Ordering),
+    /// The result appearing first in the list, results missing from it
+    /// coming after the listed ones in order of their first appearance.
+    Priority(Vec<R>),
+}
+
+impl<R: Ord> TieBreak<R> {
+    /// Chooses the smallest result.
+    pub fn smallest() -> Self {
+        return TieBreak::Order(R::cmp);
+    }
+}
+
+impl<R: Copy + Eq + Hash> TieBreak<R> {
+    /// Ranks the results of `data`, lower ranks winning ties.
+    fn ranks<T>(&self, data: &[(T, R)]) -> HashMap<R, usize> {
+        let mut results: Vec<R> =
+            utils::to_counts(data).into_iter().map(|(result, _)| result).collect();
+        match self {
+            TieBreak::FirstSeen => {}
+            TieBreak::Order(cmp) => results.sort_by(cmp),
+            TieBreak::Priority(priority) => {
+                let position = |result: &R| {
+                    priority.iter().position(|r| r == result).unwrap_or(priority.len())
+                };
+                results.sort_by_key(position);
+            }
+        }
+        return results.into_iter().enumerate().map(|(rank, result)| (result, rank)).collect();
+    }
+}
+
+/// Search of the decisions on one feature of the values, with the split options of `builder`.
+/// Compound types search the decisions on each of their fields with `FeatureSearch::field`.
+pub struct FeatureSearch<'a> {
+    pub builder: &'a TreeBuilder,
+    /// Path of the fields leading to the feature
+    feature: Vec<FeatureSegment>,
+    bin_edges: Option<&'a BinEdges>,
+}
+
+/// Step of the path from the values to one of their features.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum FeatureSegment {
+    /// Field with the given index
+    Field(usize),
+    /// Any element of a collection, as opposed to the element at a given index
+    AnyElement,
+}
+
+/// Bin edges of the features of a build, by the paths leading to them.
+#[derive(Default)]
+struct BinEdges(Mutex<HashMap<Vec<FeatureSegment>, Box<dyn Any + Send>>>);
+
+impl<'a> FeatureSearch<'a> {
+    /// Searches the decisions on the whole values, without sharing bin edges with other searches.
+    pub fn new(builder: &'a TreeBuilder) -> Self {
+        return FeatureSearch { builder, feature: Vec::new(), bin_edges: None };
+    }
+
+    /// Searches the decisions on the field with the given index.
+    pub fn field(&self, index: usize) -> Self {
+        return self.child(FeatureSegment::Field(index));
+    }
+
+    /// Searches the decisions on any element of a collection, with bin edges separate from its indexed elements.
+    pub fn any_element(&self) -> Self {
+        return self.child(FeatureSegment::AnyElement);
+    }
+
+    fn child(&self, segment: FeatureSegment) -> Self {
+        let mut feature = self.feature.clone();
+        feature.push(segment);
+        return FeatureSearch { builder: self.builder, feature, bin_edges: self.bin_edges };
+    }
+
+    /// Returns the bin edges of the feature, only computed by the first search of the build.
+    /// The root node is searched first, so the edges come from all the values of the feature.
+    pub fn bin_edges<E: Clone + Send + 'static>(&self, compute: impl FnOnce() -> E) -> E {
+        let Some(BinEdges(bin_edges)) = self.bin_edges else {
+            return compute();
+        };
+
+        if let Some(edges) = bin_edges.lock().unwrap().get(&self.feature) {
+            return edges.downcast_ref::<E>().expect("features have a single type").clone();
+        }
+
+        let edges = compute();
+        bin_edges.lock().unwrap().insert(self.feature.clone(), Box::new(edges.clone()));
+        return edges;
+    }
+}
+
+struct TreeBuilderContext<'a, R> {
+    depth: usize,
+    ranks: &'a HashMap<R, usize>,
+    bin_edges: &'a BinEdges,
+}
+
+impl<'a, R> TreeBuilderContext<'a, R> {
+    fn new(ranks: &'a HashMap<R, usize>, bin_edges: &'a BinEdges) -> Self {
+        return TreeBuilderContext { depth: 0, ranks, bin_edges };
+    }
+
+    fn next(&self) -> Self {
+        return TreeBuilderContext {
+            depth: self.depth + 1,
+            ranks: self.ranks,
+            bin_edges: self.bin_edges,
+        };
+    }
+}
+
+impl Default for TreeBuilder {
+    fn default() -> Self {
+        let max_depth = 100;
+        let threshold_placement = ThresholdPlacement::Midpoint;
+        let max_bins = None;
+        let min_samples_split = 2;
+        let pruning_confidence = None;
+        let criterion = Criterion::GainRatio;
+        return TreeBuilder {
+            max_depth,
+            threshold_placement,
+            max_bins,
+            min_samples_split,
+            pruning_confidence,
+            criterion,
+        };
+    }
+}
+
+/// Reason a tree could not be trained.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrainError {
+    /// There are no values to choose the result of the tree from.
+    NoData,
+    /// `TreeBuilder::pruning_confidence` is not between 0 and 1.
+    InvalidPruningConfidence(f64),
+}
+
+impl Display for TrainError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            TrainError::NoData => write!(f, "no training data"),
+            TrainError::InvalidPruningConfidence(confidence) => {
+                write!(f, "pruning confidence {confidence} is not between 0 and 1")
+            }
+        };
+    }
+}
+
+impl std::error::Error for TrainError {}
+
+impl TreeBuilder {
+    /// Trains a tree, ties between equally common results being broken by `TieBreak::FirstSeen`.
+    ///
+    /// Panics if the tree cannot be trained, see `try_train`.
+    pub fn train<T, R: Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        data: &mut [(T, R)],
+    ) -> Tree<T, R>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+    {
+        return self.train_with_tie_break(data, &TieBreak::FirstSeen);
+    }
+
+    /// Trains a tree, ties between equally common results being broken by `tie_break`.
+    ///
+    /// Panics if the tree cannot be trained, see `try_train_with_tie_break`.
+    pub fn train_with_tie_break<T, R: Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        data: &mut [(T, R)],
+        tie_break: &TieBreak<R>,
+    ) -> Tree<T, R>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+    {
+        return self
+            .try_train_with_tie_break(data, tie_break)
+            .unwrap_or_else(|error| panic!("{error}"));
+    }
+
+    /// Trains a tree like `train`, failing if `data` is empty or the builder is invalid.
+    pub fn try_train<T, R: Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        data: &mut [(T, R)],
+    ) -> Result<Tree<T, R>, TrainError>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+    {
+        return self.try_train_with_tie_break(data, &TieBreak::FirstSeen);
+    }
+
+    /// Trains a tree like `train_with_tie_break`, failing if `data` is empty or the builder is invalid.
+    pub fn try_train_with_tie_break<T, R: Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        data: &mut [(T, R)],
+        tie_break: &TieBreak<R>,
+    ) -> Result<Tree<T, R>, TrainError>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+    {
+        self.validate()?;
+        if data.is_empty() {
+            return Err(TrainError::NoData);
+        }
+
+        let ranks = tie_break.ranks(data);
+        let bin_edges = BinEdges::default();
+        let context = TreeBuilderContext::new(&ranks, &bin_edges);
+        return Ok(self.build_branch(&context, data));
+    }
+
+    /// Checks that the options of the builder are valid.
+    pub fn validate(&self) -> Result<(), TrainError> {
+        if let Some(confidence) = self.pruning_confidence {
+            if !(0.0..=1.0).contains(&confidence) {
+                return Err(TrainError::InvalidPruningConfidence(confidence));
+            }
+        }
+        return Ok(());
+    }
+
+    fn build_branch<T, R: Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        context: &TreeBuilderContext<R>,
+        data: &mut [(T, R)],
+    ) -> Tree<T, R>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+    {
+        let mut counts = utils::to_counts(data);
+        counts.sort_by_key(|(result, _)| context.ranks[result]);
+
+        // If there is only one possible result left in this branch,
+        // if max depth has been reached or if there are too few values to split
+        if counts.len() < 2
+            || context.depth >= self.max_depth
+            || data.len() < self.min_samples_split
+        {
+            return self.build_leaf(counts);
+        }
+
+        let entropy = utils::entropy(&counts);
+
+        let search = FeatureSearch {
+            builder: self,
+            feature: Vec::new(),
+            bin_edges: Some(context.bin_edges),
+        };
+        let decision = BranchBuilder::find_best_decision(&search, entropy, &data[..], |v| v);
+
+        let split = BranchBuilder::split_data(&mut data[..], |v| v, &decision);
+
+        if split == 0 || split == data.len() {
+            return self.build_leaf(counts);
+        }
+
+        let next_context = context.next();
+        let (true_data, false_data) = data.split_at_mut(split);
+        let (true_branch, false_branch) = parallel::join(
+            || Box::new(self.build_branch(&next_context, true_data)),
+            || Box::new(self.build_branch(&next_context, false_data)),
+        );
+
+        let tree = Tree::Branch { decision, true_branch, false_branch };
+
+        if let Some(confidence) = self.pruning_confidence {
+            let count = data.len();
+            let leaf_errors = utils::pessimistic_errors(
+                count,
+                count - counts.iter().map(|(_, count)| *count).max().unwrap(),
+                confidence,
+            );
+            if leaf_errors <= estimated_errors(&tree, confidence) + 0.1 {
+                return self.build_leaf(counts);
+            }
+        }
+
+        return tree;
+    }
+
+    /// Builds a leaf from counts ordered by rank, the first of the most common results winning.
+    fn build_leaf<T: BranchBuilder, R: Copy>(&self, counts: Vec<(R, usize)>) -> Tree<T, R> {
+        let mut best = &counts[0];
+        for count in &counts {
+            if count.1 > best.1 {
+                best = count;
+            }
+        }
+        let result = best.0;
+        return Tree::Leaf { result, counts };
+    }
+}
+
+/// Sum of the pessimistic error estimates of the leaves of `tree`.
+fn estimated_errors<T: BranchBuilder, R: Eq + Hash>(tree: &Tree<T, R>, confidence: f64) -> f64 {
+    return match tree {
+        Tree::Leaf { result, counts } => {
+            let count = counts.iter().map(|(_, count)| count).sum();
+            let correct = counts.iter().find(|(r, _)| r == result).map_or(0, |(_, count)| *count);
+            utils::pessimistic_errors(count, count - correct, confidence)
+        }
+        Tree::Branch { true_branch, false_branch, .. } => {
+            estimated_errors(true_branch, confidence) + estimated_errors(false_branch, confidence)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree_builder::{
+        BinEdges, Criterion, FeatureSearch, TieBreak, TrainError, TreeBuilder,
+    };
+    use crate::Tree;
+
+    #[test]
+    fn test_bool() {
+        let mut data = [(true, 1), (false, 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        assert!(matches!(tree, Tree::Branch { .. }));
+        assert_eq!(tree.predict(&true), 1);
+        assert_eq!(tree.predict(&false), 2);
+    }
+
+    #[test]
+    fn test_conflicted_leaf() {
+        let mut data = [(true, 1), (true, 2), (true, 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        match tree {
+            Tree::Leaf { result, counts } => {
+                assert_eq!(result, 2);
+                assert_eq!(counts, [(1, 1), (2, 2)]);
+            }
+            Tree::Branch { .. } => panic!("Expected a leaf"),
+        }
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let mut data = [(true, 3), (true, 1), (true, 2), (true, 1), (true, 2), (true, 3)];
+        let leaf = |tree: Tree<bool, i32>| {
+            match tree {
+                Tree::Leaf { result, counts } => (result, counts),
+                Tree::Branch { .. } => panic!("Expected a leaf"),
+            }
+        };
+
+        let (result, counts) = leaf(TreeBuilder::default().train(&mut data));
+        assert_eq!(result, 3);
+        assert_eq!(counts, [(3, 2), (1, 2), (2, 2)]);
+
+        let (result, counts) =
+            leaf(TreeBuilder::default().train_with_tie_break(&mut data, &TieBreak::smallest()));
+        assert_eq!(result, 1);
+        assert_eq!(counts, [(1, 2), (2, 2), (3, 2)]);
+
+        let priority = TieBreak::Priority(vec![2]);
+        let (result, counts) =
+            leaf(TreeBuilder::default().train_with_tie_break(&mut data, &priority));
+        assert_eq!(result, 2);
+        assert_eq!(counts, [(2, 2), (3, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_tie_break_in_branches() {
+        // Both leaves are conflicted, the rank of the results being the same in each of them
+        let mut data = [(true, 'b'), (true, 'a'), (false, 'a'), (false, 'b'), (false, 'c')];
+        let tree = TreeBuilder::default().train(&mut data.clone());
+        assert_eq!(tree.predict(&true), 'b');
+        assert_eq!(tree.predict(&false), 'b');
+        let tree = TreeBuilder::default().train_with_tie_break(&mut data, &TieBreak::smallest());
+        assert_eq!(tree.predict(&true), 'a');
+        assert_eq!(tree.predict(&false), 'a');
+    }
+
+    fn depth<T: crate::BranchBuilder, R>(tree: &Tree<T, R>) -> usize {
+        return match tree {
+            Tree::Leaf { .. } => 0,
+            Tree::Branch { true_branch, false_branch, .. } => {
+                1 + depth(true_branch).max(depth(false_branch))
+            }
+        };
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut data = [((0, 0), 1), ((0, 1), 2), ((1, 0), 2), ((1, 1), 1)];
+        assert_eq!(
+            depth(&TreeBuilder { max_depth: 0, ..TreeBuilder::default() }.train(&mut data)),
+            0
+        );
+        assert_eq!(
+            depth(&TreeBuilder { max_depth: 1, ..TreeBuilder::default() }.train(&mut data)),
+            1
+        );
+        assert_eq!(depth(&TreeBuilder::default().train(&mut data)), 2);
+    }
+
+    #[test]
+    fn test_min_samples_split() {
+        let mut data = [(0, 1), (1, 1), (2, 1), (3, 2), (4, 2), (5, 3)];
+        assert_eq!(depth(&TreeBuilder::default().train(&mut data)), 2);
+        assert_eq!(
+            depth(&TreeBuilder { min_samples_split: 6, ..TreeBuilder::default() }.train(&mut data)),
+            1
+        );
+        assert_eq!(
+            depth(&TreeBuilder { min_samples_split: 7, ..TreeBuilder::default() }.train(&mut data)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_pruning() {
+        // A single value with a different result is not worth a branch
+        let mut data: Vec<(u32, bool)> = (0..20).map(|i| (i, i == 7)).collect();
+        let tree = TreeBuilder { pruning_confidence: Some(0.25), ..TreeBuilder::default() }
+            .train(&mut data);
+        assert!(matches!(tree, Tree::Leaf { result: false, .. }));
+
+        let mut data: Vec<(u32, bool)> = (0..20).map(|i| (i, i < 10)).collect();
+        let tree = TreeBuilder { pruning_confidence: Some(0.25), ..TreeBuilder::default() }
+            .train(&mut data);
+        assert_eq!(depth(&tree), 1);
+    }
+
+    #[test]
+    fn test_train_errors() {
+        let mut data: [(u32, bool); 0] = [];
+        assert!(matches!(TreeBuilder::default().try_train(&mut data), Err(TrainError::NoData)));
+
+        let mut data = [(1u32, true), (2, false)];
+        for confidence in [f64::NAN, -0.1, 1.5] {
+            let builder =
+                TreeBuilder { pruning_confidence: Some(confidence), ..TreeBuilder::default() };
+            assert!(matches!(
+                builder.try_train(&mut data),
+                Err(TrainError::InvalidPruningConfidence(_))
+            ));
+        }
+        let builder = TreeBuilder { pruning_confidence: Some(1.0), ..TreeBuilder::default() };
+        assert!(builder.try_train(&mut data).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "no training data")]
+    fn test_train_empty() {
+        let mut data: [(u32, bool); 0] = [];
+        TreeBuilder::default().train(&mut data);
+    }
+
+    #[test]
+    fn test_criteria() {
+        let mut data: Vec<((u32, u32), u8)> =
+            (0..30).map(|i| ((i % 6, i / 6), (i % 6 / 2 + i / 6 % 2) as u8)).collect();
+        for criterion in [Criterion::GainRatio, Criterion::InformationGain, Criterion::Gini] {
+            let tree = TreeBuilder { criterion, ..TreeBuilder::default() }.train(&mut data);
+            for (val, result) in &data {
+                assert_eq!(tree.predict(val), *result, "{criterion:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_bins() {
+        let mut data: Vec<(u32, bool)> =
+            (0..1000).map(|i| ((i * 7) % 1000, (i * 7) % 1000 >= 500)).collect();
+        let tree = TreeBuilder { max_bins: Some(4), ..TreeBuilder::default() }.train(&mut data);
+        // The children reuse the bin edges of the root, 255, 515 and 775,
+        // so the values of the bin containing the boundary can not be split further
+        for (val, result) in &data {
+            if !(500..515).contains(val) {
+                assert_eq!(tree.predict(val), *result);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bin_edges_shared() {
+        let builder = TreeBuilder::default();
+        let bin_edges = BinEdges::default();
+        let search =
+            FeatureSearch { builder: &builder, feature: Vec::new(), bin_edges: Some(&bin_edges) };
+        assert_eq!(search.bin_edges(|| vec![1, 2]), vec![1, 2]);
+        assert_eq!(search.bin_edges(|| vec![3]), vec![1, 2]);
+        assert_eq!(search.field(0).bin_edges(|| vec![3]), vec![3]);
+        assert_eq!(search.field(0).any_element().bin_edges(|| vec![5]), vec![5]);
+        assert_eq!(search.field(0).field(16).bin_edges(|| vec![6]), vec![6]);
+        assert_eq!(search.field(0).any_element().bin_edges(|| vec![7]), vec![5]);
+        assert_eq!(FeatureSearch::new(&builder).bin_edges(|| vec![4]), vec![4]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_thread_count_independent() {
+        type Val = ([u8; 3], (u8, bool));
+
+        let data: Vec<(Val, u8)> = (0..500u32)
+            .map(|i| {
+                let x = i.wrapping_mul(2654435761).to_le_bytes();
+                let val = ([x[0] % 7, x[1] % 5, x[2] % 11], (x[3] % 3, x[0] % 2 == 0));
+                let result = (val.0[0] + val.0[2] * (val.1 .1 as u8)) % 4;
+                (val, result)
+            })
+            .collect();
+
+        let train = |num_threads| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(num_threads).build().unwrap();
+            return pool.install(|| {
+                let mut data = data.clone();
+                let tree = TreeBuilder::default().train(&mut data);
+                return data.iter().map(|(val, _)| tree.predict(val)).collect::<Vec<_>>();
+            });
+        };
+
+        assert_eq!(train(1), train(4));
+    }
+}
@@ -0,0 +1,1012 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{
+    Criterion, Decision, DecisionEval, EqDecision, FeatureSearch, ObliqueDecision, OrdDecision, StringDecision,
+    ThresholdPlacement, TreeBuilder,
+};
+
+pub fn split_data<T, F>(data: &mut [T], predicate: F) -> usize
+where F: Fn(&T) -> bool {
+    if data.is_empty() {
+        return 0;
+    }
+
+    let mut a = 0;
+    let mut b = data.len() - 1;
+    while a != b {
+        while predicate(&data[a]) && a < b {
+            a += 1;
+        }
+        while !predicate(&data[b]) && a < b {
+            b -= 1;
+        }
+        data.swap(a, b);
+    }
+
+    // The last element is only checked when every element before it matched
+    if predicate(&data[a]) {
+        a += 1;
+    }
+
+    return a;
+}
+
+pub fn h(count: usize, total: usize) -> f64 {
+    if count == 0 {
+        return 0.0;
+    }
+    let p = count as f64 / total as f64;
+    let result = -p * p.log2();
+    debug_assert!(!result.is_nan());
+    return result;
+}
+
+pub fn entropy<T>(counts: &[(T, usize)]) -> f64 {
+    let total = counts.iter().map(|(_, count)| count).sum();
+    let mut result = 0.0;
+    for (_, i) in counts {
+        result += h(*i, total);
+    }
+    return result;
+}
+
+/// Counts the values of each result, in order of the first appearance of the results,
+/// so the counts do not depend on the hashing of the results.
+pub fn to_counts<D, R: Eq + Hash + Copy>(data: &[(D, R)]) -> Vec<(R, usize)> {
+    let mut indices = HashMap::new();
+    let mut results = vec![];
+
+    for (_, res) in data.iter() {
+        let index = *indices.entry(*res).or_insert_with(|| {
+            results.push((*res, 0));
+            return results.len() - 1;
+        });
+        results[index].1 += 1;
+    }
+
+    return results;
+}
+
+/// Maps every result to a dense class index, in order of the first appearance,
+/// and returns the indices together with the number of classes.
+pub fn class_indices<D, R: Eq + Hash + Copy>(data: &[(D, R)]) -> (Vec<usize>, usize) {
+    let mut classes = HashMap::new();
+    let indices = data
+        .iter()
+        .map(|(_, res)| {
+            let next = classes.len();
+            return *classes.entry(*res).or_insert(next);
+        })
+        .collect();
+
+    return (indices, classes.len());
+}
+
+/// Gini impurity of class counts summing up to `sum`.
+fn gini(counts: &[usize], sum: usize) -> f64 {
+    if sum == 0 {
+        return 0.0;
+    }
+    return 1.0 - counts.iter().map(|count| (*count as f64 / sum as f64).powi(2)).sum::<f64>();
+}
+
+/// Evaluates a binary split from the class counts of both of its branches, by the criterion.
+/// `entropy` is the entropy of the values before the split, the Gini impurity being computed from the counts.
+pub fn evaluate_split(criterion: Criterion, entropy: f64, true_counts: &[usize], false_counts: &[usize]) -> DecisionEval {
+    let true_sum: usize = true_counts.iter().sum();
+    let false_sum: usize = false_counts.iter().sum();
+    let total_count = true_sum + false_sum;
+
+    let mut info = 0.0;
+    for (counts, sum) in [(true_counts, true_sum), (false_counts, false_sum)] {
+        let i = match criterion {
+            Criterion::GainRatio | Criterion::InformationGain => {
+                let mut i = 0.0;
+                for count in counts {
+                    i += h(*count, sum);
+                }
+                i
+            }
+            Criterion::Gini => gini(counts, sum),
+        };
+        info += i * sum as f64 / total_count as f64;
+    }
+
+    let gain_ratio = match criterion {
+        Criterion::GainRatio => {
+            let split_info = h(true_sum, total_count) + h(false_sum, total_count);
+            if split_info == 0.0 {
+                0.0
+            } else {
+                (entropy - info) / split_info
+            }
+        }
+        Criterion::InformationGain => entropy - info,
+        Criterion::Gini => {
+            let counts: Vec<usize> = true_counts.iter().zip(false_counts).map(|(t, f)| t + f).collect();
+            gini(&counts, total_count) - info
+        }
+    };
+
+    return DecisionEval { gain_ratio, max_branch_width: true_sum.max(false_sum) };
+}
+
+/// Upper limit of the error count of a leaf with `count` values, `errors` of them misclassified,
+/// at the given confidence factor between 0 and 1, estimated as in C4.5. NaN for a NaN confidence.
+pub fn pessimistic_errors(count: usize, errors: usize, confidence: f64) -> f64 {
+    // Deviations of the normal distribution for the confidence factors, interpolated in between
+    const CONFIDENCES: [f64; 9] = [0.0, 0.001, 0.005, 0.01, 0.05, 0.1, 0.2, 0.4, 1.0];
+    const DEVIATIONS: [f64; 9] = [4.0, 3.09, 2.58, 2.33, 1.65, 1.28, 0.84, 0.25, 0.0];
+
+    let confidence = confidence.clamp(0.0, 1.0);
+    let i = CONFIDENCES.iter().position(|c| confidence <= *c).map_or(CONFIDENCES.len() - 1, |i| i.max(1));
+    let deviation = DEVIATIONS[i - 1]
+        + (DEVIATIONS[i] - DEVIATIONS[i - 1]) * (confidence - CONFIDENCES[i - 1]) / (CONFIDENCES[i] - CONFIDENCES[i - 1]);
+    let coefficient = deviation * deviation;
+
+    if count == 0 {
+        return 0.0;
+    }
+
+    let n = count as f64;
+    let e = errors as f64;
+
+    let extra_errors = if errors == 0 {
+        n * (1.0 - (confidence.ln() / n).exp())
+    } else if e + 0.5 >= n {
+        0.67 * (n - e)
+    } else {
+        let e = e + 0.5;
+        let probability = (e + coefficient / 2.0 + (coefficient * (e * (1.0 - e / n) + coefficient / 4.0)).sqrt()) / (n + coefficient);
+        n * probability - (e - 0.5)
+    };
+    return e + extra_errors;
+}
+
+/// Whether `candidate` should replace `best`, preferring the higher gain ratio
+/// and then the more balanced split.
+fn is_better(candidate: &DecisionEval, best: &DecisionEval) -> bool {
+    return candidate.gain_ratio > best.gain_ratio
+        || (candidate.gain_ratio == best.gain_ratio && candidate.max_branch_width < best.max_branch_width);
+}
+
+/// Total order used for sorting values, placing incomparable values (NaN) last.
+fn total_cmp<V: PartialOrd>(a: &V, b: &V) -> Ordering {
+    return match a.partial_cmp(b) {
+        Some(ordering) => ordering,
+        None => a.partial_cmp(a).is_none().cmp(&b.partial_cmp(b).is_none()),
+    };
+}
+
+/// Number of sampled values per bin used to estimate the quantiles of the binned search.
+const SAMPLES_PER_BIN: usize = 16;
+
+/// Sweeps over groups of sorted values, keeping track of the best threshold between two groups.
+struct ThresholdSweep<V> {
+    criterion: Criterion,
+    entropy: f64,
+    true_counts: Vec<usize>,
+    false_counts: Vec<usize>,
+    previous: Option<(V, Option<usize>)>,
+    best_lower: Option<V>,
+    best_threshold: V,
+    best_eval: DecisionEval,
+}
+
+impl<V: PartialOrd + Copy> ThresholdSweep<V> {
+    /// Starts with every value in the false branch and the smallest value as the threshold.
+    fn new(criterion: Criterion, entropy: f64, total_counts: Vec<usize>, first: V) -> Self {
+        let true_counts = vec![0; total_counts.len()];
+        let best_eval = evaluate_split(criterion, entropy, &true_counts, &total_counts);
+        return ThresholdSweep {
+            criterion,
+            entropy,
+            true_counts,
+            false_counts: total_counts,
+            previous: None,
+            best_lower: None,
+            best_threshold: first,
+            best_eval,
+        };
+    }
+
+    /// Evaluates the threshold before the next group, containing values between `min` and `max`,
+    /// and moves the group into the true branch.
+    ///
+    /// The threshold is skipped unless it is a boundary point, as a threshold between two groups
+    /// containing only the same single class can never be the best one.
+    fn push(&mut self, min: V, max: V, counts: impl Iterator<Item = (usize, usize)> + Clone) {
+        // `None` when the group contains more than one class
+        let mut classes = counts.clone().filter(|(_, count)| *count > 0).map(|(class, _)| class);
+        let class = classes.next().filter(|_| classes.next().is_none());
+
+        if let Some((lower, previous_class)) = self.previous {
+            let is_boundary = class.is_none() || class != previous_class;
+            if is_boundary && min.partial_cmp(&min).is_some() {
+                let eval = evaluate_split(self.criterion, self.entropy, &self.true_counts, &self.false_counts);
+                if is_better(&eval, &self.best_eval) {
+                    self.best_lower = Some(lower);
+                    self.best_threshold = min;
+                    self.best_eval = eval;
+                }
+            }
+        }
+
+        for (class, count) in counts {
+            self.true_counts[class] += count;
+            self.false_counts[class] -= count;
+        }
+        self.previous = Some((max, class));
+    }
+
+    /// Places the best threshold between its neighbouring values.
+    fn finish(self, placement: ThresholdPlacement, midpoint: impl Fn(V, V) -> Option<V>) -> OrdDecision<V> {
+        let (threshold, inclusive) = match (placement, self.best_lower) {
+            (ThresholdPlacement::Lower, Some(lower)) => (lower, true),
+            (ThresholdPlacement::Midpoint, Some(lower)) => {
+                (midpoint(lower, self.best_threshold).unwrap_or(self.best_threshold), false)
+            }
+            _ => (self.best_threshold, false),
+        };
+
+        return OrdDecision { decision_eval: self.best_eval, threshold, inclusive };
+    }
+}
+
+/// Finds the best threshold split of values paired with their class indices.
+///
+/// The values are sorted once and the class counts are accumulated while sweeping over them,
+/// evaluating each distinct value as a threshold. With `max_bins` set on the builder and more values
+/// than bins, the thresholds are only searched between bins instead, see [`find_best_binned_threshold`].
+/// The threshold is then placed between the neighbouring values, using `midpoint` if it returns a value.
+pub fn find_best_threshold<V: PartialOrd + Copy + Send + 'static>(
+    search: &FeatureSearch,
+    entropy: f64,
+    mut vals: Vec<(V, usize)>,
+    class_count: usize,
+    midpoint: impl Fn(V, V) -> Option<V>,
+) -> OrdDecision<V> {
+    let builder = search.builder;
+    if let Some(max_bins) = builder.max_bins {
+        if vals.len() > max_bins {
+            return find_best_binned_threshold(search, entropy, vals, class_count, max_bins, midpoint);
+        }
+    }
+
+    vals.sort_by(|(a, _), (b, _)| total_cmp(a, b));
+
+    let mut total_counts = vec![0; class_count];
+    for (_, class) in &vals {
+        total_counts[*class] += 1;
+    }
+
+    let mut sweep = ThresholdSweep::new(builder.criterion, entropy, total_counts, vals[0].0);
+
+    let mut start = 0;
+    while start < vals.len() {
+        let val = vals[start].0;
+        let mut end = start + 1;
+        while end < vals.len() && total_cmp(&vals[end].0, &val) == Ordering::Equal {
+            end += 1;
+        }
+
+        sweep.push(val, val, vals[start..end].iter().map(|(_, class)| (*class, 1)));
+        start = end;
+    }
+
+    return sweep.finish(builder.threshold_placement, midpoint);
+}
+
+/// Quantiles of an evenly spaced sample of the values, splitting them into at most `max_bins` bins,
+/// or `None` if the sample only contains NaN values.
+fn bin_edges<V: PartialOrd + Copy>(vals: &[(V, usize)], max_bins: usize) -> Option<Vec<V>> {
+    let step = (vals.len() / (max_bins * SAMPLES_PER_BIN)).max(1);
+    let mut sample: Vec<V> =
+        vals.iter().step_by(step).map(|(val, _)| *val).filter(|val| val.partial_cmp(val).is_some()).collect();
+
+    if sample.is_empty() {
+        return None;
+    }
+
+    sample.sort_by(total_cmp);
+    let mut edges: Vec<V> = (1..max_bins).map(|i| sample[i * sample.len() / max_bins]).collect();
+    edges.dedup_by(|a, b| a == b);
+    return Some(edges);
+}
+
+/// Finds the best threshold split after grouping the values into at most `max_bins` bins.
+///
+/// The bin edges are computed once per build by [`FeatureSearch::bin_edges`], from a sorted sample
+/// of the values of the root node, so the values of the other nodes are never sorted and only
+/// the boundaries between bins are evaluated. The thresholds still come from the actual values
+/// on both sides of the boundary. NaN values are not binned and always stay in the false branch.
+pub fn find_best_binned_threshold<V: PartialOrd + Copy + Send + 'static>(
+    search: &FeatureSearch,
+    entropy: f64,
+    vals: Vec<(V, usize)>,
+    class_count: usize,
+    max_bins: usize,
+    midpoint: impl Fn(V, V) -> Option<V>,
+) -> OrdDecision<V> {
+    let exact_builder = TreeBuilder { max_bins: None, ..*search.builder };
+    let Some(edges) = search.bin_edges(|| bin_edges(&vals, max_bins)) else {
+        return find_best_threshold(&FeatureSearch::new(&exact_builder), entropy, vals, class_count, midpoint);
+    };
+
+    // Minimum, maximum and class counts of each bin
+    let mut bins: Vec<Option<(V, V)>> = vec![None; edges.len() + 1];
+    let mut bin_counts = vec![vec![0; class_count]; edges.len() + 1];
+    let mut total_counts = vec![0; class_count];
+
+    for (val, class) in &vals {
+        total_counts[*class] += 1;
+        if val.partial_cmp(val).is_none() {
+            continue;
+        }
+
+        let bin = edges.partition_point(|edge| edge <= val);
+        bin_counts[bin][*class] += 1;
+        bins[bin] = match bins[bin] {
+            Some((min, max)) => Some((if val < &min { *val } else { min }, if val > &max { *val } else { max })),
+            None => Some((*val, *val)),
+        };
+    }
+
+    let Some((first, _)) = bins.iter().flatten().next() else {
+        return find_best_threshold(&FeatureSearch::new(&exact_builder), entropy, vals, class_count, midpoint);
+    };
+    let mut sweep = ThresholdSweep::new(search.builder.criterion, entropy, total_counts, *first);
+
+    for (bin, counts) in bins.iter().zip(&bin_counts) {
+        if let Some((min, max)) = bin {
+            sweep.push(*min, *max, counts.iter().copied().enumerate());
+        }
+    }
+
+    return sweep.finish(search.builder.threshold_placement, midpoint);
+}
+
+/// Maximum number of rounds perturbing every coefficient of an oblique split.
+const MAX_OBLIQUE_ROUNDS: usize = 10;
+
+/// Evaluates the split of values by an oblique decision.
+fn evaluate_oblique<const N: usize>(
+    criterion: Criterion,
+    entropy: f64,
+    vals: &[([f64; N], usize)],
+    class_count: usize,
+    decision: &ObliqueDecision<N>,
+) -> DecisionEval {
+    let mut true_counts = vec![0; class_count];
+    let mut false_counts = vec![0; class_count];
+    for (values, class) in vals {
+        if decision.matches(values) {
+            true_counts[*class] += 1;
+        } else {
+            false_counts[*class] += 1;
+        }
+    }
+    return evaluate_split(criterion, entropy, &true_counts, &false_counts);
+}
+
+/// Finds a good `weights · values < threshold` split of values paired with their class indices.
+///
+/// Starts from the best split on a single value and then, similarly to OC1, repeatedly perturbs
+/// one coefficient of the hyperplane at a time (the threshold included), setting it to the best value
+/// found by sweeping over the values at which a sample would cross the hyperplane.
+pub fn find_best_oblique<const N: usize>(
+    criterion: Criterion,
+    entropy: f64,
+    vals: &[([f64; N], usize)],
+    class_count: usize,
+) -> ObliqueDecision<N> {
+    let builder =
+        TreeBuilder { criterion, threshold_placement: ThresholdPlacement::Midpoint, max_bins: None, ..TreeBuilder::default() };
+    let midpoint = |lower: f64, upper: f64| {
+        let midpoint = lower / 2.0 + upper / 2.0;
+        return (lower < midpoint && midpoint <= upper).then_some(midpoint);
+    };
+
+    let mut best: Option<ObliqueDecision<N>> = None;
+    for i in 0..N {
+        let feature = vals.iter().map(|(values, class)| (values[i], *class)).collect();
+        let decision = find_best_threshold(&FeatureSearch::new(&builder), entropy, feature, class_count, midpoint);
+        if best.as_ref().is_none_or(|best| is_better(&decision.decision_eval, &best.decision_eval)) {
+            let mut weights = [0.0; N];
+            weights[i] = 1.0;
+            best = Some(ObliqueDecision { decision_eval: decision.decision_eval, weights, threshold: decision.threshold });
+        }
+    }
+    let mut best = best.expect("oblique splits need at least one value");
+
+    for _ in 0..MAX_OBLIQUE_ROUNDS {
+        let mut improved = false;
+        for m in 0..=N {
+            if let Some(decision) = perturb_oblique(criterion, entropy, vals, class_count, &best, m) {
+                best = decision;
+                improved = true;
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    return best;
+}
+
+/// Finds the best value of the coefficient `m` of the hyperplane (`N` being the threshold),
+/// returning the perturbed decision if it is better than `decision`.
+fn perturb_oblique<const N: usize>(
+    criterion: Criterion,
+    entropy: f64,
+    vals: &[([f64; N], usize)],
+    class_count: usize,
+    decision: &ObliqueDecision<N>,
+    m: usize,
+) -> Option<ObliqueDecision<N>> {
+    // With `h = weights · values - threshold` the sample is in the true branch when `h < 0`,
+    // and setting the coefficient to `u = coefficient - h / z` puts the sample on the hyperplane,
+    // `z` being the value the coefficient is multiplied with (-1 for the threshold)
+    let coefficient = if m == N { decision.threshold } else { decision.weights[m] };
+
+    let mut true_counts = vec![0; class_count];
+    let mut false_counts = vec![0; class_count];
+    let mut crossings = vec![];
+    for (values, class) in vals {
+        let h: f64 = decision.weights.iter().zip(values).filter(|(w, _)| **w != 0.0).map(|(w, v)| w * v).sum::<f64>()
+            - decision.threshold;
+        let z = if m == N { -1.0 } else { values[m] };
+        let u = coefficient - h / z;
+
+        if z == 0.0 || !u.is_finite() {
+            if h < 0.0 {
+                true_counts[*class] += 1;
+            } else {
+                false_counts[*class] += 1;
+            }
+            continue;
+        }
+
+        // For a very small coefficient the sample is in the true branch when `z > 0`,
+        // and switches branches once the coefficient is larger than `u`
+        if z > 0.0 {
+            true_counts[*class] += 1;
+        } else {
+            false_counts[*class] += 1;
+        }
+        crossings.push((u, z > 0.0, *class));
+    }
+
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut best_eval = None;
+    let mut best_coefficient = coefficient;
+    let mut start = 0;
+    while start < crossings.len() {
+        let u = crossings[start].0;
+        let mut end = start + 1;
+        while end < crossings.len() && crossings[end].0 == u {
+            end += 1;
+        }
+
+        for (_, positive, class) in &crossings[start..end] {
+            if *positive {
+                true_counts[*class] -= 1;
+                false_counts[*class] += 1;
+            } else {
+                false_counts[*class] -= 1;
+                true_counts[*class] += 1;
+            }
+        }
+
+        if end < crossings.len() {
+            let next = crossings[end].0;
+            let midpoint = u / 2.0 + next / 2.0;
+            if u < midpoint && midpoint < next {
+                let eval = evaluate_split(criterion, entropy, &true_counts, &false_counts);
+                if best_eval.as_ref().is_none_or(|best| is_better(&eval, best)) {
+                    best_eval = Some(eval);
+                    best_coefficient = midpoint;
+                }
+            }
+        }
+        start = end;
+    }
+
+    best_eval?;
+
+    let mut perturbed = ObliqueDecision { decision_eval: DecisionEval { gain_ratio: 0.0, max_branch_width: 0 }, ..*decision };
+    if m == N {
+        perturbed.threshold = best_coefficient;
+    } else {
+        perturbed.weights[m] = best_coefficient;
+    }
+
+    // Rounding may move samples lying close to the hyperplane, so the split is evaluated again
+    perturbed.decision_eval = evaluate_oblique(criterion, entropy, vals, class_count, &perturbed);
+    return is_better(&perturbed.decision_eval, &decision.decision_eval).then_some(perturbed);
+}
+
+/// Finds the best set of values to split on, by checking whether a value is one of them,
+/// of values paired with their class indices.
+///
+/// The class counts of each distinct value are collected in a single pass. Every single value
+/// is evaluated first, in order of their first appearance. Larger sets are then taken from the values
+/// ordered by the proportion of each class, which finds the best set for two classes,
+/// and is used as a heuristic for more classes. The values of the set are in order of their first appearance.
+pub fn find_best_values<'a, V: Eq + Hash + ?Sized>(
+    criterion: Criterion,
+    entropy: f64,
+    vals: impl Iterator<Item = (&'a V, usize)>,
+    class_count: usize,
+) -> (Vec<&'a V>, DecisionEval) {
+    let mut indices = HashMap::new();
+    let mut groups: Vec<(&V, Vec<usize>)> = vec![];
+    let mut total_counts = vec![0; class_count];
+
+    for (val, class) in vals {
+        let index = *indices.entry(val).or_insert_with(|| {
+            groups.push((val, vec![0; class_count]));
+            return groups.len() - 1;
+        });
+        groups[index].1[class] += 1;
+        total_counts[class] += 1;
+    }
+
+    let mut best_set = vec![0];
+    let mut best_eval = DecisionEval { gain_ratio: 0.0, max_branch_width: usize::MAX };
+    let mut false_counts = vec![0; class_count];
+
+    for (i, (_, true_counts)) in groups.iter().enumerate() {
+        for (f, (total, t)) in false_counts.iter_mut().zip(total_counts.iter().zip(true_counts)) {
+            *f = total - t;
+        }
+
+        let eval = evaluate_split(criterion, entropy, true_counts, &false_counts);
+        if is_better(&eval, &best_eval) {
+            best_set = vec![i];
+            best_eval = eval;
+        }
+    }
+
+    // With two values any set is equivalent to a single value, and with two classes
+    // the orderings by the proportions of either class are the reverse of each other
+    let ordered_classes = if groups.len() <= 2 {
+        0
+    } else if class_count == 2 {
+        1
+    } else {
+        class_count
+    };
+    let group_sizes: Vec<usize> = groups.iter().map(|(_, counts)| counts.iter().sum()).collect();
+
+    for class in 0..ordered_classes {
+        let mut order: Vec<usize> = (0..groups.len()).collect();
+        order.sort_by(|a, b| {
+            let proportion_a = groups[*a].1[class] * group_sizes[*b];
+            let proportion_b = groups[*b].1[class] * group_sizes[*a];
+            return proportion_b.cmp(&proportion_a);
+        });
+
+        let mut true_counts = vec![0; class_count];
+        let mut false_counts = total_counts.clone();
+        for size in 1..groups.len() {
+            for (c, count) in groups[order[size - 1]].1.iter().enumerate() {
+                true_counts[c] += count;
+                false_counts[c] -= count;
+            }
+            if size == 1 {
+                continue;
+            }
+
+            let eval = evaluate_split(criterion, entropy, &true_counts, &false_counts);
+            if is_better(&eval, &best_eval) {
+                best_set = order[..size].to_vec();
+                best_set.sort();
+                best_eval = eval;
+            }
+        }
+    }
+
+    return (best_set.into_iter().map(|i| groups[i].0).collect(), best_eval);
+}
+
+/// Maximum number of characters of the prefixes, suffixes and substrings searched by string decisions.
+const MAX_PATTERN_CHARS: usize = 16;
+
+/// Maximum number of values the candidate patterns of string decisions are mined from.
+const MAX_MINED_VALUES: usize = 256;
+
+/// Maximum number of candidate patterns of each kind evaluated by string decisions.
+const MAX_PATTERN_CANDIDATES: usize = 1024;
+
+#[derive(Clone, Copy)]
+enum PatternKind {
+    Prefix,
+    Suffix,
+    Substring,
+}
+
+/// Returns the non-empty patterns of `val` of the given kind, with up to `MAX_PATTERN_CHARS` characters.
+fn string_patterns(val: &str, kind: PatternKind) -> Vec<&str> {
+    let boundaries: Vec<usize> = val.char_indices().map(|(i, _)| i).chain(std::iter::once(val.len())).collect();
+    let char_count = boundaries.len() - 1;
+    let max_chars = MAX_PATTERN_CHARS.min(char_count);
+
+    return match kind {
+        PatternKind::Prefix => (1..=max_chars).map(|chars| &val[..boundaries[chars]]).collect(),
+        PatternKind::Suffix => (1..=max_chars).map(|chars| &val[boundaries[char_count - chars]..]).collect(),
+        PatternKind::Substring => (0..char_count)
+            .flat_map(|start| {
+                let max_end = (start + MAX_PATTERN_CHARS).min(char_count);
+                return (start + 1..=max_end).map(move |end| (start, end));
+            })
+            .map(|(start, end)| &val[boundaries[start]..boundaries[end]])
+            .collect(),
+    };
+}
+
+/// Mines the candidate patterns of the given kind from an evenly spaced sample of at most `MAX_MINED_VALUES`
+/// values, keeping the `MAX_PATTERN_CANDIDATES` patterns contained in the most sampled values.
+/// The candidates are returned in order of their first appearance.
+fn pattern_candidates<'v>(vals: &[(&'v str, usize)], kind: PatternKind) -> Vec<&'v str> {
+    let step = vals.len().div_ceil(MAX_MINED_VALUES).max(1);
+
+    // Patterns in order of their first appearance, with the number of sampled values containing them
+    let mut indices = HashMap::new();
+    let mut patterns: Vec<(&str, usize, usize)> = vec![];
+    for (i, (val, _)) in vals.iter().step_by(step).enumerate() {
+        for pattern in string_patterns(val, kind) {
+            let index = *indices.entry(pattern).or_insert_with(|| {
+                patterns.push((pattern, 0, usize::MAX));
+                return patterns.len() - 1;
+            });
+
+            let (_, count, last_val) = &mut patterns[index];
+            if *last_val != i {
+                *count += 1;
+                *last_val = i;
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..patterns.len()).collect();
+    if patterns.len() > MAX_PATTERN_CANDIDATES {
+        order.sort_by_key(|index| Reverse(patterns[*index].1));
+        order.truncate(MAX_PATTERN_CANDIDATES);
+        order.sort();
+    }
+    return order.into_iter().map(|index| patterns[index].0).collect();
+}
+
+/// Finds the best decision on strings, by equality to a set of values, by prefixes, suffixes and substrings
+/// of up to `MAX_PATTERN_CHARS` characters taken from the values, or by thresholds on the length.
+/// The patterns are only searched among the candidates of [`pattern_candidates`].
+/// On equally good decisions, equality to a single value is preferred, followed by the patterns,
+/// and the length, which all generalize better than equality to several values.
+pub fn find_best_string_decision(
+    search: &FeatureSearch,
+    entropy: f64,
+    vals: &[(&str, usize)],
+    class_count: usize,
+) -> StringDecision {
+    let mut best: Option<StringDecision> = None;
+    let mut total_counts = vec![0; class_count];
+    for (_, class) in vals {
+        total_counts[*class] += 1;
+    }
+
+    for kind in [PatternKind::Prefix, PatternKind::Suffix, PatternKind::Substring] {
+        // Candidate patterns, with the counts of all the values containing them
+        let mut patterns: Vec<(&str, Vec<usize>, usize)> = pattern_candidates(vals, kind)
+            .into_iter()
+            .map(|pattern| (pattern, vec![0; class_count], usize::MAX))
+            .collect();
+        let indices: HashMap<&str, usize> = patterns.iter().enumerate().map(|(index, (pattern, ..))| (*pattern, index)).collect();
+
+        for (i, (val, class)) in vals.iter().enumerate() {
+            for pattern in string_patterns(val, kind) {
+                let Some(index) = indices.get(pattern) else {
+                    continue;
+                };
+
+                // A value is only counted once, even if the pattern occurs in it several times
+                let (_, counts, last_val) = &mut patterns[*index];
+                if *last_val != i {
+                    counts[*class] += 1;
+                    *last_val = i;
+                }
+            }
+        }
+
+        let mut false_counts = vec![0; class_count];
+        for (pattern, true_counts, _) in patterns {
+            for (f, (total, t)) in false_counts.iter_mut().zip(total_counts.iter().zip(&true_counts)) {
+                *f = total - t;
+            }
+
+            let decision_eval = evaluate_split(search.builder.criterion, entropy, &true_counts, &false_counts);
+            if best.as_ref().is_none_or(|best| is_better(&decision_eval, best.to_decision_eval())) {
+                let pattern = pattern.to_string();
+                best = Some(match kind {
+                    PatternKind::Prefix => StringDecision::StartsWith { decision_eval, pattern },
+                    PatternKind::Suffix => StringDecision::EndsWith { decision_eval, pattern },
+                    PatternKind::Substring => StringDecision::Contains { decision_eval, pattern },
+                });
+            }
+        }
+    }
+
+    let lens = vals.iter().map(|(val, class)| (val.len(), *class)).collect();
+    let len = find_best_threshold(search, entropy, lens, class_count, |_lower, _upper| None);
+    let best = match best {
+        Some(best) if !is_better(&len.decision_eval, best.to_decision_eval()) => best,
+        _ => StringDecision::Len(len),
+    };
+
+    let eq_vals = vals.iter().map(|(val, class)| (*val, *class));
+    let (eq_vals, decision_eval) = find_best_values(search.builder.criterion, entropy, eq_vals, class_count);
+    let prefer_eq = if eq_vals.len() == 1 {
+        !is_better(best.to_decision_eval(), &decision_eval)
+    } else {
+        is_better(&decision_eval, best.to_decision_eval())
+    };
+
+    return if prefer_eq {
+        StringDecision::Eq(EqDecision { decision_eval, vals: eq_vals.into_iter().map(String::from).collect() })
+    } else {
+        best
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small() {
+        let mut data = [false, true];
+        let split = split_data(&mut data, |v| *v);
+        assert_eq!(split, 1);
+        assert_eq!(data, [true, false]);
+    }
+
+    #[test]
+    fn test_middle() {
+        let mut data = [9, 1, 4, 8, 3, 7, 3, 1];
+        let split = split_data(&mut data, |v| v < &5);
+        assert_eq!(split, 5);
+        assert_eq!(data, [1, 1, 4, 3, 3, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_start() {
+        let mut data = [9, 1, 4, 8, 3, 7, 3, 1];
+        let split = split_data(&mut data, |v| v < &2);
+        assert_eq!(split, 2);
+        assert_eq!(data, [1, 1, 4, 8, 3, 7, 3, 9]);
+    }
+
+    #[test]
+    fn test_end() {
+        let mut data = [9, 1, 4, 8, 3, 7, 3, 1];
+        let split = split_data(&mut data, |v| v < &9);
+        assert_eq!(split, 7);
+        assert_eq!(data, [1, 1, 4, 8, 3, 7, 3, 9]);
+    }
+
+    #[test]
+    fn test_all() {
+        let mut data = [1, 2, 3];
+        let split = split_data(&mut data, |v| v < &5);
+        assert_eq!(split, 3);
+        assert_eq!(data, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_none() {
+        let mut data = [6, 7, 8];
+        let split = split_data(&mut data, |v| v < &5);
+        assert_eq!(split, 0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut data: [usize; 0] = [];
+        let split = split_data(&mut data, |v| v < &5);
+        assert_eq!(split, 0);
+    }
+
+    #[test]
+    fn test_class_indices() {
+        let data = [((), "b"), ((), "a"), ((), "b"), ((), "c")];
+        assert_eq!(class_indices(&data), (vec![0, 1, 0, 2], 3));
+    }
+
+    #[test]
+    fn test_evaluate_split() {
+        let eval = evaluate_split(Criterion::GainRatio, 1.0, &[2, 0], &[0, 2]);
+        assert_eq!(eval.gain_ratio, 1.0);
+        assert_eq!(eval.max_branch_width, 2);
+
+        let eval = evaluate_split(Criterion::GainRatio, 1.0, &[0, 0], &[2, 2]);
+        assert_eq!(eval.gain_ratio, 0.0);
+        assert_eq!(eval.max_branch_width, 4);
+
+        assert_eq!(evaluate_split(Criterion::InformationGain, 1.0, &[2, 0], &[0, 2]).gain_ratio, 1.0);
+        assert_eq!(evaluate_split(Criterion::Gini, 1.0, &[2, 0], &[0, 2]).gain_ratio, 0.5);
+
+        // Splitting off a single value, which the gain ratio favours the most
+        let score = |criterion| evaluate_split(criterion, 1.0, &[1, 0], &[1, 2]).gain_ratio;
+        assert!((score(Criterion::GainRatio) - 0.3837).abs() < 0.0001);
+        assert!((score(Criterion::InformationGain) - 0.3113).abs() < 0.0001);
+        assert!((score(Criterion::Gini) - 1.0 / 6.0).abs() < 0.0001);
+    }
+
+    fn placement(threshold_placement: ThresholdPlacement) -> TreeBuilder {
+        return TreeBuilder { threshold_placement, ..TreeBuilder::default() };
+    }
+
+    #[test]
+    fn test_find_best_threshold() {
+        let vals = vec![(5, 1), (1, 0), (3, 0), (7, 1), (3, 0)];
+        let decision = find_best_threshold(&FeatureSearch::new(&placement(ThresholdPlacement::Upper)), 0.9709505944546686, vals, 2, |_, _| None);
+        assert_eq!(decision.threshold, 5);
+        assert!(!decision.inclusive);
+        assert_eq!(decision.decision_eval.max_branch_width, 3);
+    }
+
+    #[test]
+    fn test_find_best_threshold_single_value() {
+        let decision = find_best_threshold(&FeatureSearch::new(&placement(ThresholdPlacement::Lower)), 1.0, vec![(2, 0), (2, 1)], 2, |_, _| None);
+        assert_eq!(decision.threshold, 2);
+        assert!(!decision.inclusive);
+        assert_eq!(decision.decision_eval.gain_ratio, 0.0);
+        assert_eq!(decision.decision_eval.max_branch_width, 2);
+    }
+
+    #[test]
+    fn test_threshold_placement() {
+        let vals = vec![(1.0, 0), (2.0, 0), (4.0, 1), (5.0, 1)];
+        let midpoint = |lower: f64, upper: f64| Some((lower + upper) / 2.0);
+
+        let decision = find_best_threshold(&FeatureSearch::new(&placement(ThresholdPlacement::Lower)), 1.0, vals.clone(), 2, midpoint);
+        assert_eq!((decision.threshold, decision.inclusive), (2.0, true));
+
+        let decision = find_best_threshold(&FeatureSearch::new(&placement(ThresholdPlacement::Upper)), 1.0, vals.clone(), 2, midpoint);
+        assert_eq!((decision.threshold, decision.inclusive), (4.0, false));
+
+        let decision = find_best_threshold(&FeatureSearch::new(&placement(ThresholdPlacement::Midpoint)), 1.0, vals, 2, midpoint);
+        assert_eq!((decision.threshold, decision.inclusive), (3.0, false));
+    }
+
+    #[test]
+    fn test_threshold_nan() {
+        let vals = vec![(f64::NAN, 1), (1.0, 0), (f64::NAN, 1), (3.0, 1)];
+        let decision = find_best_threshold(&FeatureSearch::new(&placement(ThresholdPlacement::Upper)), 1.0, vals, 2, |_, _| None);
+        assert_eq!(decision.threshold, 3.0);
+        assert_eq!(decision.decision_eval.max_branch_width, 3);
+    }
+
+    #[test]
+    fn test_find_best_binned_threshold() {
+        let builder = TreeBuilder { max_bins: Some(4), threshold_placement: ThresholdPlacement::Upper, ..TreeBuilder::default() };
+        let vals: Vec<(u32, usize)> = (0..1000).map(|i| (i % 8, (i % 8 >= 4) as usize)).collect();
+        let decision = find_best_threshold(&FeatureSearch::new(&builder), 1.0, vals, 2, |_, _| None);
+        assert_eq!(decision.threshold, 4);
+        assert_eq!(decision.decision_eval.gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_find_best_binned_threshold_nan() {
+        let builder = TreeBuilder { max_bins: Some(2), ..TreeBuilder::default() };
+        let vals = vec![(f64::NAN, 1), (1.0, 0), (2.0, 0), (f64::NAN, 1), (3.0, 1), (4.0, 1)];
+        let decision = find_best_threshold(&FeatureSearch::new(&builder), 1.0, vals, 2, |lower: f64, upper: f64| Some((lower + upper) / 2.0));
+        assert_eq!(decision.threshold, 2.5);
+        assert_eq!(decision.decision_eval.max_branch_width, 4);
+    }
+
+    #[test]
+    fn test_find_best_oblique() {
+        let vals: Vec<([f64; 2], usize)> = (0..100)
+            .map(|i| {
+                let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+                return ([x, y], (x > y) as usize);
+            })
+            .collect();
+        let counts = [55, 45];
+        let entropy = h(counts[0], 100) + h(counts[1], 100);
+
+        let decision = find_best_oblique(Criterion::GainRatio, entropy, &vals, 2);
+        for (values, class) in &vals {
+            assert_eq!(decision.matches(values), *class == 0);
+        }
+    }
+
+    #[test]
+    fn test_find_best_values_single() {
+        let vals = ["a", "b", "c", "b"];
+        let classes = [0, 1, 0, 1];
+        let (set, eval) = find_best_values(Criterion::GainRatio, 1.0, vals.iter().zip(classes), 2);
+        assert_eq!(set, [&"b"]);
+        assert_eq!(eval.gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_find_best_values_set() {
+        let vals = ["a", "b", "c", "d", "a", "b", "c", "d"];
+        let classes = [0, 1, 1, 0, 0, 1, 1, 0];
+        let (set, eval) = find_best_values(Criterion::GainRatio, 1.0, vals.iter().zip(classes), 2);
+        assert_eq!(set, [&"a", &"d"]);
+        assert_eq!(eval.gain_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_find_best_values_multi_class() {
+        let vals = ["a", "b", "c", "d", "e", "f", "c"];
+        let classes = [0, 1, 2, 0, 1, 0, 1];
+        let counts = [3, 3, 1];
+        let entropy = counts.iter().map(|count| h(*count, 7)).sum();
+        let (set, _) = find_best_values(Criterion::GainRatio, entropy, vals.iter().zip(classes), 3);
+        assert_eq!(set, [&"a", &"d", &"f"]);
+    }
+
+    #[test]
+    fn test_string_patterns() {
+        assert_eq!(string_patterns("äbc", PatternKind::Prefix), ["ä", "äb", "äbc"]);
+        assert_eq!(string_patterns("äbc", PatternKind::Suffix), ["c", "bc", "äbc"]);
+        assert_eq!(string_patterns("äbc", PatternKind::Substring), ["ä", "äb", "äbc", "b", "bc", "c"]);
+        assert_eq!(string_patterns("", PatternKind::Substring), Vec::<&str>::new());
+        assert_eq!(string_patterns(&"a".repeat(20), PatternKind::Prefix).len(), MAX_PATTERN_CHARS);
+    }
+
+    #[test]
+    fn test_pattern_candidates() {
+        let strings: Vec<String> = (0..10_000).map(|i| format!("id-{i:06}")).collect();
+        let vals: Vec<(&str, usize)> = strings.iter().map(|val| (val.as_str(), 0)).collect();
+        let candidates = pattern_candidates(&vals, PatternKind::Substring);
+        assert_eq!(candidates.len(), MAX_PATTERN_CANDIDATES);
+        assert_eq!(candidates[..3], ["i", "id", "id-"]);
+
+        let vals = [("ab", 0), ("b", 0)];
+        assert_eq!(pattern_candidates(&vals, PatternKind::Substring), ["a", "ab", "b"]);
+    }
+
+    #[test]
+    fn test_find_best_string_decision() {
+        let vals = [("x/api/a", 0), ("y/api/b", 0), ("x/web/a", 1), ("y/web/b", 1)];
+        let entropy = h(2, 4) * 2.0;
+        let decision = find_best_string_decision(&FeatureSearch::new(&TreeBuilder::default()), entropy, &vals, 2);
+        assert!(matches!(&decision, StringDecision::Contains { pattern, .. } if pattern == "/ap"));
+        assert!(decision.matches("z/api"));
+        assert!(!decision.matches("z/web"));
+    }
+
+    #[test]
+    fn test_pessimistic_errors() {
+        // Values from the C4.5 book, with a confidence factor of 0.25
+        assert!((pessimistic_errors(6, 0, 0.25) - 1.24).abs() < 0.01);
+        assert!((pessimistic_errors(9, 0, 0.25) - 1.29).abs() < 0.01);
+        assert!((pessimistic_errors(1, 0, 0.25) - 0.75).abs() < 0.01);
+        assert!((pessimistic_errors(16, 1, 0.25) - 2.51).abs() < 0.01);
+        assert!(pessimistic_errors(16, 1, 0.1) > pessimistic_errors(16, 1, 0.25));
+        assert_eq!(pessimistic_errors(0, 0, 0.25), 0.0);
+        assert_eq!(pessimistic_errors(16, 1, 1.5), pessimistic_errors(16, 1, 1.0));
+        assert!(pessimistic_errors(16, 1, f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_entropy() {
+        let counts = [("A", 9), ("B", 5)];
+        assert_eq!(entropy(&counts), 0.9402859586706311);
+    }
+
+    #[test]
+    fn test_to_counts() {
+        let data = [((), 'b'), ((), 'a'), ((), 'b'), ((), 'c')];
+        assert_eq!(to_counts(&data), [('b', 2), ('a', 1), ('c', 1)]);
+    }
+}
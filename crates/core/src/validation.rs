@@ -0,0 +1,207 @@
+use std::hash::Hash;
+
+use crate::{evaluate, BranchBuilder, Criterion, Evaluation, MaybeSend, MaybeSync, TreeBuilder};
+
+/// Results of a k-fold cross-validation.
+pub struct CrossValidation<R> {
+    /// Evaluation of the tree trained on the other folds, for each fold.
+    pub folds: Vec<Evaluation<R>>,
+    /// Mean accuracy over the folds.
+    pub accuracy: f64,
+}
+
+/// Settings searched by `grid_search`, every combination of the values being evaluated.
+#[derive(Clone, Debug)]
+pub struct Grid {
+    pub max_depths: Vec<usize>,
+    pub min_samples_splits: Vec<usize>,
+    pub pruning_confidences: Vec<Option<f64>>,
+    pub criteria: Vec<Criterion>,
+}
+
+/// Results of a grid search, the best configuration being the first one with the highest accuracy.
+pub struct GridSearch {
+    pub best: TreeBuilder,
+    pub best_accuracy: f64,
+    /// Every evaluated configuration with its cross-validated accuracy.
+    pub results: Vec<(TreeBuilder, f64)>,
+}
+
+/// Small deterministic random number generator (SplitMix64), so folds only depend on the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        return z ^ (z >> 31);
+    }
+
+    /// Fisher-Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Assigns the indices of `data` to `k` folds, shuffled with the seed and stratified by result,
+/// so each fold has about the same share of every result.
+fn stratified_folds<T, R: Eq>(data: &[(T, R)], k: usize, seed: u64) -> Vec<Vec<usize>> {
+    // Indices of each result, in order of their first appearance
+    let mut groups: Vec<(&R, Vec<usize>)> = vec![];
+    for (i, (_, result)) in data.iter().enumerate() {
+        match groups.iter_mut().find(|(group_result, _)| *group_result == result) {
+            Some((_, indices)) => indices.push(i),
+            None => groups.push((result, vec![i])),
+        }
+    }
+
+    let mut rng = Rng(seed);
+    let mut folds = vec![vec![]; k];
+    let mut fold = 0;
+    for (_, indices) in &mut groups {
+        rng.shuffle(indices);
+        for index in indices.iter() {
+            folds[fold].push(*index);
+            fold = (fold + 1) % k;
+        }
+    }
+    return folds;
+}
+
+/// Trains a tree with `builder` on `k - 1` folds of `data` and evaluates it on the remaining one, for each fold.
+/// The folds are shuffled with the seed and stratified by result.
+pub fn cross_validate<T, R>(builder: &TreeBuilder, data: &[(T, R)], k: usize, seed: u64) -> CrossValidation<R>
+where
+    T: BranchBuilder + MaybeSync,
+    R: Copy + Eq + Hash + MaybeSend + MaybeSync,
+{
+    assert!(k >= 2 && k <= data.len(), "cross-validation needs 2 to {} folds, got {k}", data.len());
+
+    let folds = stratified_folds(data, k, seed);
+    let folds: Vec<Evaluation<R>> = folds
+        .iter()
+        .enumerate()
+        .map(|(i, test)| {
+            // Values are borrowed, the tree deciding on them through the reference
+            let mut train: Vec<(&T, R)> = folds
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .flat_map(|(_, fold)| fold.iter().map(|index| (&data[*index].0, data[*index].1)))
+                .collect();
+            let test: Vec<(&T, R)> = test.iter().map(|index| (&data[*index].0, data[*index].1)).collect();
+
+            let tree = builder.train(&mut train);
+            return evaluate(&tree, &test);
+        })
+        .collect();
+
+    let accuracy = folds.iter().map(|fold| fold.accuracy).sum::<f64>() / k as f64;
+    return CrossValidation { folds, accuracy };
+}
+
+impl Grid {
+    /// Returns `base` with every combination of the settings of the grid.
+    pub fn builders(&self, base: &TreeBuilder) -> Vec<TreeBuilder> {
+        let mut builders = vec![];
+        for max_depth in &self.max_depths {
+            for min_samples_split in &self.min_samples_splits {
+                for pruning_confidence in &self.pruning_confidences {
+                    for criterion in &self.criteria {
+                        builders.push(TreeBuilder {
+                            max_depth: *max_depth,
+                            min_samples_split: *min_samples_split,
+                            pruning_confidence: *pruning_confidence,
+                            criterion: *criterion,
+                            ..base.clone()
+                        });
+                    }
+                }
+            }
+        }
+        return builders;
+    }
+}
+
+/// Cross-validates every combination of the settings of `grid` applied to `base` with `k` folds,
+/// and returns the configuration with the highest mean accuracy.
+pub fn grid_search<T, R>(base: &TreeBuilder, grid: &Grid, data: &[(T, R)], k: usize, seed: u64) -> GridSearch
+where
+    T: BranchBuilder + MaybeSync,
+    R: Copy + Eq + Hash + MaybeSend + MaybeSync,
+{
+    let results: Vec<(TreeBuilder, f64)> = grid
+        .builders(base)
+        .into_iter()
+        .map(|builder| {
+            let accuracy = cross_validate(&builder, data, k, seed).accuracy;
+            return (builder, accuracy);
+        })
+        .collect();
+
+    let mut best = results.first().expect("the grid has no configurations");
+    for result in &results {
+        if result.1 > best.1 {
+            best = result;
+        }
+    }
+
+    let (best, best_accuracy) = best.clone();
+    return GridSearch { best, best_accuracy, results };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stratified_folds() {
+        let data: Vec<(u32, bool)> = (0..20).map(|i| (i, i < 5)).collect();
+        let folds = stratified_folds(&data, 5, 1);
+        for fold in &folds {
+            assert_eq!(fold.len(), 4);
+            assert_eq!(fold.iter().filter(|i| data[**i].1).count(), 1);
+        }
+
+        let mut indices: Vec<usize> = folds.concat();
+        indices.sort();
+        assert_eq!(indices, (0..20).collect::<Vec<usize>>());
+
+        assert_eq!(stratified_folds(&data, 5, 1), folds);
+        assert_ne!(stratified_folds(&data, 5, 2), folds);
+    }
+
+    #[test]
+    fn test_cross_validate() {
+        let data: Vec<((u32, u32), bool)> = (0..40).map(|i| ((i, i % 7), i >= 20)).collect();
+        let cross_validation = cross_validate(&TreeBuilder::default(), &data, 4, 0);
+        assert_eq!(cross_validation.folds.len(), 4);
+        assert!(cross_validation.accuracy > 0.95);
+    }
+
+    #[test]
+    fn test_grid_search() {
+        // The second value is noise, only useful for memorizing the training data
+        let data: Vec<((u32, u32), bool)> = (0..60).map(|i| ((i % 10, (i * 37) % 11), i % 10 >= 5 || i % 13 == 0)).collect();
+        let grid = Grid {
+            max_depths: vec![1, 2, 10],
+            min_samples_splits: vec![2, 10],
+            pruning_confidences: vec![None, Some(0.25)],
+            criteria: vec![Criterion::GainRatio, Criterion::InformationGain, Criterion::Gini],
+        };
+        let search = grid_search(&TreeBuilder::default(), &grid, &data, 5, 0);
+
+        assert_eq!(search.results.len(), 36);
+        assert_eq!(search.best.max_depth, 1);
+        assert!(grid.criteria.contains(&search.best.criterion));
+        for criterion in &grid.criteria {
+            assert!(search.results.iter().any(|(builder, _)| builder.criterion == *criterion));
+        }
+        assert!(search.results.iter().all(|(_, accuracy)| *accuracy <= search.best_accuracy));
+    }
+}
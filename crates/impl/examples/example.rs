@@ -1,9 +1,8 @@
 use std::hash::Hash;
 
-use decision_tree_builder_impl::{BranchBuilder, Decision, DecisionEval, TreeBuilder};
+use decision_tree_builder_impl::{BranchBuilder, BuildCode, Decision, DecisionEval, FeatureSearch, MaybeSync, ToCondition, TreeBuilder};
 use proc_macro2::{Ident, Punct, Spacing, Span, TokenStream};
-use quote::{quote, ToTokens, TokenStreamExt};
-use syn::__private::TokenStream2;
+use quote::{quote, TokenStreamExt};
 
 struct TestStructData {
     a: Inner,
@@ -19,21 +18,20 @@ impl BranchBuilder for TestStructData {
     type Decision = __TestStructDataDecision;
 
     #[allow(unused_assignments)]
-    fn find_best_decision<R: Copy + Eq + Hash, F, D>(entropy: f64, data: &mut [(D, R)], extract: F) -> Self::Decision
-    where F: Fn(&D) -> &Self {
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(search: &FeatureSearch, entropy: f64, data: &'a [(D, R)], extract: F) -> Self::Decision
+    where F: Fn(&'a D) -> &'a Self + MaybeSync, Self: 'a {
         let decisions = [
-            __TestStructDataDecision::A(BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).a)),
-            __TestStructDataDecision::B(BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).b)),
+            __TestStructDataDecision::A(BranchBuilder::find_best_decision(&search.field(0), entropy, data, |d| &extract(d).a)),
+            __TestStructDataDecision::B(BranchBuilder::find_best_decision(&search.field(1), entropy, data, |d| &extract(d).b)),
         ];
 
         return decisions.into_iter().max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval())).unwrap();
     }
 
-    fn split_data<F, D, R>(data: &mut [(D, R)], extract: F, decision: &Self::Decision) -> usize
-    where F: Fn(&D) -> &Self {
+    fn matches(&self, decision: &Self::Decision) -> bool {
         return match decision {
-            __TestStructDataDecision::A(a) => BranchBuilder::split_data(data, |d| &extract(d).a, a),
-            __TestStructDataDecision::B(b) => BranchBuilder::split_data(data, |d| &extract(d).b, b),
+            __TestStructDataDecision::A(a) => self.a.matches(a),
+            __TestStructDataDecision::B(b) => self.b.matches(b),
         };
     }
 }
@@ -50,7 +48,9 @@ impl Decision for __TestStructDataDecision {
             __TestStructDataDecision::B(b) => b.to_decision_eval(),
         };
     }
+}
 
+impl ToCondition for __TestStructDataDecision {
     fn to_condition(&self, var: TokenStream) -> TokenStream {
         return match self {
             __TestStructDataDecision::A(a) => a.to_condition(quote!(#var.a)),
@@ -63,21 +63,20 @@ impl BranchBuilder for Inner {
     type Decision = __InnerDecision;
 
     #[allow(unused_assignments)]
-    fn find_best_decision<R: Copy + Eq + Hash, F, D>(entropy: f64, data: &mut [(D, R)], extract: F) -> Self::Decision
-    where F: Fn(&D) -> &Self {
+    fn find_best_decision<'a, R: Copy + Eq + Hash + MaybeSync, F, D: MaybeSync>(search: &FeatureSearch, entropy: f64, data: &'a [(D, R)], extract: F) -> Self::Decision
+    where F: Fn(&'a D) -> &'a Self + MaybeSync, Self: 'a {
         let decisions = [
-            __InnerDecision::A(BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).a)),
-            __InnerDecision::B(BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).b)),
+            __InnerDecision::A(BranchBuilder::find_best_decision(&search.field(0), entropy, data, |d| &extract(d).a)),
+            __InnerDecision::B(BranchBuilder::find_best_decision(&search.field(1), entropy, data, |d| &extract(d).b)),
         ];
 
         return decisions.into_iter().max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval())).unwrap();
     }
 
-    fn split_data<F, D, R>(data: &mut [(D, R)], extract: F, decision: &Self::Decision) -> usize
-    where F: Fn(&D) -> &Self {
+    fn matches(&self, decision: &Self::Decision) -> bool {
         return match decision {
-            __InnerDecision::A(a) => BranchBuilder::split_data(data, |d| &extract(d).a, a),
-            __InnerDecision::B(b) => BranchBuilder::split_data(data, |d| &extract(d).b, b),
+            __InnerDecision::A(a) => self.a.matches(a),
+            __InnerDecision::B(b) => self.b.matches(b),
         };
     }
 }
@@ -94,7 +93,9 @@ impl Decision for __InnerDecision {
             __InnerDecision::B(b) => b.to_decision_eval(),
         };
     }
+}
 
+impl ToCondition for __InnerDecision {
     fn to_condition(&self, var: TokenStream) -> TokenStream {
         let mut result = TokenStream::new();
         result.append_all(var);
@@ -109,12 +110,6 @@ impl Decision for __InnerDecision {
                 inner.to_condition(result)
             }
         };
-        return result.into();
-
-        // match self {
-        //     __InnerDecision::A(a) => a.to_condition(quote!(#var.a)),
-        //     __InnerDecision::B(b) => b.to_condition(quote!(#var.b)),
-        // };
     }
 }
 
@@ -0,0 +1,63 @@
+//! Items used by code generated by the `BranchBuilder` derive.
+//!
+//! Not part of the public API, re-exported so that generated code
+//! only needs to depend on this crate.
+
+use proc_macro2::{Delimiter, Ident, Literal, Span, TokenTree};
+pub use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+/// Appends access to the given field to `var`, e.g. `val.a` or `val.0` for tuple structs.
+pub fn access_field(var: TokenStream, field: &str) -> TokenStream {
+    let field = match field.parse::<usize>() {
+        Ok(index) => Literal::usize_unsuffixed(index).to_token_stream(),
+        Err(_) => Ident::new(field, Span::call_site()).to_token_stream(),
+    };
+    let var = auto_deref(var);
+    return quote!(#var.#field);
+}
+
+/// Removes explicit dereferences such as `(*val)` from `var`, for use where Rust dereferences
+/// automatically: field access, method calls and indexing.
+pub fn auto_deref(var: TokenStream) -> TokenStream {
+    let mut tokens = var.clone().into_iter();
+    if let (Some(TokenTree::Group(group)), None) = (tokens.next(), tokens.next()) {
+        if group.delimiter() == Delimiter::Parenthesis {
+            let mut inner = group.stream().into_iter();
+            if let Some(TokenTree::Punct(punct)) = inner.next() {
+                if punct.as_char() == '*' {
+                    return auto_deref(inner.collect());
+                }
+            }
+        }
+    }
+    return var;
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn test_named_field() {
+        let result = access_field(quote!(val), "a");
+        assert_eq!(result.to_string(), quote!(val.a).to_string());
+    }
+
+    #[test]
+    fn test_dereferenced_field() {
+        let result = access_field(quote!((*(*val))), "a");
+        assert_eq!(result.to_string(), quote!(val.a).to_string());
+
+        let result = access_field(quote!((*val.b)), "a");
+        assert_eq!(result.to_string(), quote!(val.b.a).to_string());
+    }
+
+    #[test]
+    fn test_unnamed_field() {
+        let result = access_field(quote!(val.b), "0");
+        assert_eq!(result.to_string(), quote!(val.b.0).to_string());
+    }
+}
@@ -0,0 +1,610 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use decision_tree_builder_core::{BranchBuilder, Explanation, MaybeSend, MaybeSync, Rule, TrainError, Tree, TreeBuilder};
+use proc_macro2::{LexError, Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::{ToCondition, ToFormattedTokens};
+
+pub struct CodeGenerator {
+    pub show_conflicted_leaves: bool,
+    /// Also generates `decide_explain`, returning the result together with the conditions tested
+    /// for the value, whether it matched them, and the counts of the training results of the reached leaf.
+    pub explain: bool,
+    /// Generates subtrees occurring more than once as helper functions called by `decide`.
+    pub deduplicate_subtrees: bool,
+}
+
+#[derive(Debug)]
+pub enum TreeBuilderError {
+    /// A type name could not be parsed as tokens.
+    Lex(LexError),
+    /// A lookup table was requested but would have `len` entries, more than `max_table_len`.
+    TableTooLong { len: Option<usize>, max_table_len: usize },
+    /// The tree could not be trained.
+    Train(TrainError),
+}
+
+impl From<LexError> for TreeBuilderError {
+    fn from(error: LexError) -> Self {
+        return TreeBuilderError::Lex(error);
+    }
+}
+
+impl From<TrainError> for TreeBuilderError {
+    fn from(error: TrainError) -> Self {
+        return TreeBuilderError::Train(error);
+    }
+}
+
+impl Display for TreeBuilderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            TreeBuilderError::Lex(error) => write!(f, "{error}"),
+            TreeBuilderError::TableTooLong { len: Some(len), max_table_len } => {
+                write!(f, "lookup table of {len} entries is longer than {max_table_len}")
+            }
+            TreeBuilderError::TableTooLong { len: None, max_table_len } => {
+                write!(f, "lookup table does not fit in memory, the maximum is {max_table_len}")
+            }
+            TreeBuilderError::Train(error) => write!(f, "{error}"),
+        };
+    }
+}
+
+impl std::error::Error for TreeBuilderError {}
+
+impl Default for CodeGenerator {
+    fn default() -> Self {
+        let show_conflicted_leaves = false;
+        let explain = false;
+        let deduplicate_subtrees = false;
+        return CodeGenerator { show_conflicted_leaves, explain, deduplicate_subtrees };
+    }
+}
+
+impl CodeGenerator {
+    pub fn generate<T, R: ToFormattedTokens + Copy + Eq + Hash>(
+        &self,
+        tree: &Tree<T, R>,
+    ) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder,
+        T::Decision: ToCondition,
+    {
+        let result_type = TokenStream::from_str(std::any::type_name::<R>())?;
+        let input_type = TokenStream::from_str(&type_path(std::any::type_name::<T>()))?;
+        let decide = if self.deduplicate_subtrees {
+            let mut subtrees = Subtrees { ids: HashMap::new(), occurrences: HashMap::new(), helper_indices: HashMap::new(), helpers: vec![] };
+            self.index_subtrees(tree, &mut HashMap::new(), &mut subtrees.ids);
+            count_subtrees(tree, &subtrees.ids, &mut subtrees.occurrences);
+            let inner = self.generate_deduplicated(tree, &mut subtrees, &input_type, &result_type, false)?;
+            let helpers = &subtrees.helpers;
+            quote!(
+                pub fn decide(val: &#input_type) -> #result_type {
+                    return #inner;
+                }
+
+                #(#helpers)*
+            )
+        } else {
+            let inner = self.generate_branch(tree, false)?;
+            quote!(pub fn decide(val: &#input_type) -> #result_type {
+                return #inner;
+            })
+        };
+        if !self.explain {
+            return Ok(decide);
+        }
+
+        let inner = self.generate_branch(tree, true)?;
+        return Ok(quote!(
+            #decide
+
+            pub fn decide_explain(val: &#input_type) -> (#result_type, Vec<(&'static str, bool)>, Vec<(#result_type, usize)>) {
+                #[allow(unused_mut)]
+                let mut path = Vec::new();
+                return #inner;
+            }
+        ));
+    }
+
+    fn generate_branch<T, R: ToFormattedTokens + Copy + Eq + Hash>(
+        &self,
+        tree: &Tree<T, R>,
+        explain: bool,
+    ) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder,
+        T::Decision: ToCondition,
+    {
+        let (decision, true_branch, false_branch) = match tree {
+            Tree::Leaf { result, counts } if explain => return Ok(explained_leaf(result, counts)),
+            Tree::Leaf { result, counts } => return Ok(self.generate_leaf(result, counts)),
+            Tree::Branch { decision, true_branch, false_branch } => (decision, true_branch, false_branch),
+        };
+
+        // Conditions are rendered on the value, `val` being a reference to it
+        let mut condition = decision.to_condition(quote!((*val)));
+        if explain {
+            let text = format_condition(decision);
+            condition = quote!({
+                let matched = #condition;
+                path.push((#text, matched));
+                matched
+            });
+        }
+
+        let branch_a = self.generate_branch(true_branch, explain)?;
+        let branch_b = self.generate_branch(false_branch, explain)?;
+
+        return Ok(quote!(
+            if #condition {
+                #branch_a
+            } else {
+                #branch_b
+            }
+        ));
+    }
+
+    /// Numbers the code of every node bottom-up, a branch being identified by its condition and the ids of
+    /// its children, so that nodes generating the same code get the same id. Returns the id of `tree`.
+    fn index_subtrees<T, R: ToFormattedTokens + Copy + Eq + Hash>(
+        &self,
+        tree: &Tree<T, R>,
+        codes: &mut HashMap<SubtreeCode, usize>,
+        ids: &mut HashMap<*const (), usize>,
+    ) -> usize
+    where
+        T: BranchBuilder,
+        T::Decision: ToCondition,
+    {
+        let code = match tree {
+            Tree::Leaf { result, counts } => SubtreeCode::Leaf(self.generate_leaf(result, counts).to_string()),
+            Tree::Branch { decision, true_branch, false_branch } => {
+                let true_id = self.index_subtrees(true_branch, codes, ids);
+                let false_id = self.index_subtrees(false_branch, codes, ids);
+                SubtreeCode::Branch(decision.to_condition(quote!((*val))).to_string(), true_id, false_id)
+            }
+        };
+        let next_id = codes.len();
+        let id = *codes.entry(code).or_insert(next_id);
+        ids.insert(address(tree), id);
+        return id;
+    }
+
+    /// Generates a branch, calling the helper function of subtrees occurring more than once.
+    /// `is_helper` is set when generating the body of the helper of `tree` itself.
+    fn generate_deduplicated<T, R: ToFormattedTokens + Copy + Eq + Hash>(
+        &self,
+        tree: &Tree<T, R>,
+        subtrees: &mut Subtrees,
+        input_type: &TokenStream,
+        result_type: &TokenStream,
+        is_helper: bool,
+    ) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder,
+        T::Decision: ToCondition,
+    {
+        let (decision, true_branch, false_branch) = match tree {
+            Tree::Leaf { result, counts } => return Ok(self.generate_leaf(result, counts)),
+            Tree::Branch { decision, true_branch, false_branch } => (decision, true_branch, false_branch),
+        };
+
+        let id = subtrees.ids[&address(tree)];
+        if !is_helper && subtrees.occurrences[&id] > 1 {
+            let index = match subtrees.helper_indices.get(&id) {
+                Some(index) => *index,
+                None => {
+                    // Helpers called by this one are added first
+                    let inner = self.generate_deduplicated(tree, subtrees, input_type, result_type, true)?;
+                    let name = format_ident!("decide_subtree_{}", subtrees.helpers.len());
+                    let helper = quote!(fn #name(val: &#input_type) -> #result_type {
+                        return #inner;
+                    });
+                    subtrees.helper_indices.insert(id, subtrees.helpers.len());
+                    subtrees.helpers.push(helper);
+                    subtrees.helpers.len() - 1
+                }
+            };
+            let name = format_ident!("decide_subtree_{index}");
+            return Ok(quote!(#name(val)));
+        }
+
+        let condition = decision.to_condition(quote!((*val)));
+        let branch_a = self.generate_deduplicated(true_branch, subtrees, input_type, result_type, false)?;
+        let branch_b = self.generate_deduplicated(false_branch, subtrees, input_type, result_type, false)?;
+
+        return Ok(quote!(
+            if #condition {
+                #branch_a
+            } else {
+                #branch_b
+            }
+        ));
+    }
+
+    fn generate_leaf<R: Eq + ToFormattedTokens>(&self, result: &R, map: &[(R, usize)]) -> TokenStream {
+        let result = result.to_formatted_tokens();
+        let mut comment = TokenStream::new();
+        if map.len() > 1 && self.show_conflicted_leaves {
+            for (r, c) in map {
+                let label = r.to_formatted_tokens();
+                let count = Literal::usize_unsuffixed(*c);
+                comment = quote!(#comment #[tree_builder_conflicted_leaf(#label = #count)])
+            }
+        }
+        return quote!(#comment #result);
+    }
+}
+
+/// Counts the occurrences of the code of every branch, once for each distinct code containing it.
+fn count_subtrees<T: BranchBuilder, R>(tree: &Tree<T, R>, ids: &HashMap<*const (), usize>, occurrences: &mut HashMap<usize, usize>) {
+    if let Tree::Branch { true_branch, false_branch, .. } = tree {
+        let count = occurrences.entry(ids[&address(tree)]).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            count_subtrees(true_branch, ids, occurrences);
+            count_subtrees(false_branch, ids, occurrences);
+        }
+    }
+}
+
+/// Address of a node, identifying it within the tree.
+fn address<T: BranchBuilder, R>(tree: &Tree<T, R>) -> *const () {
+    return tree as *const Tree<T, R> as *const ();
+}
+
+/// Code of a node, its children being referred to by the ids of their code.
+#[derive(PartialEq, Eq, Hash)]
+enum SubtreeCode {
+    Leaf(String),
+    Branch(String, usize, usize),
+}
+
+/// Helper functions generated for subtrees occurring more than once.
+struct Subtrees {
+    /// Id of the code of every node, by the address of the node.
+    ids: HashMap<*const (), usize>,
+    /// Occurrences of the code of every branch, by id.
+    occurrences: HashMap<usize, usize>,
+    /// Index of the helper function of the code of every id.
+    helper_indices: HashMap<usize, usize>,
+    helpers: Vec<TokenStream>,
+}
+
+/// Leaf of `decide_explain`, returning the result with the path and the counts of the leaf.
+fn explained_leaf<R: ToFormattedTokens>(result: &R, counts: &[(R, usize)]) -> TokenStream {
+    let result = result.to_formatted_tokens();
+    let counts = counts.iter().map(|(r, c)| {
+        let label = r.to_formatted_tokens();
+        let count = Literal::usize_unsuffixed(*c);
+        return quote!((#label, #count));
+    });
+    return quote!((#result, path, vec![#(#counts),*]));
+}
+
+/// Renders the condition of a decision as formatted code on the value `val`, e.g. `val.a < 1`.
+pub fn format_condition<D: ToCondition>(decision: &D) -> String {
+    let condition = decision.to_condition(quote!((*val)));
+    let Ok(file) = syn::parse2::<syn::File>(quote!(fn condition() { #condition })) else {
+        return condition.to_string();
+    };
+
+    // Only the body of the function is kept, on a single line
+    let code = prettyplease::unparse(&file);
+    let lines: Vec<&str> = code.lines().map(str::trim).collect();
+    return lines[1..lines.len() - 1].join(" ").replace("( ", "(").replace(", )", ")");
+}
+
+/// Renders an explanation returned by `Tree::explain`, with one line for each condition tested for the value,
+/// formatted by `format_condition`, and a last line with the result and the counts of the reached leaf.
+pub fn format_explanation<T, R: ToFormattedTokens>(explanation: &Explanation<T, R>) -> String
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let mut lines = vec![];
+    for (decision, matched) in &explanation.path {
+        lines.push(format!("{}: {matched}", format_condition(*decision)));
+    }
+    lines.push(format!("result: {} ({})", explanation.result.to_formatted_tokens(), format_counts(explanation.counts)));
+    return lines.join("\n");
+}
+
+/// Renders rules returned by `Tree::rules` or `simplify_rules`, one per line, with the conditions formatted
+/// by `format_condition`, e.g. `IF val.a < 1 AND NOT (val.b) THEN 2 (support: 3, confidence: 0.667)`.
+pub fn format_rules<T, R: ToFormattedTokens>(rules: &[Rule<T, R>]) -> String
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let mut lines = vec![];
+    for rule in rules {
+        let conditions: Vec<String> = rule
+            .conditions
+            .iter()
+            .map(|(decision, matched)| {
+                let condition = format_condition(*decision);
+                return if *matched { condition } else { format!("NOT ({condition})") };
+            })
+            .collect();
+        let conditions = if conditions.is_empty() { String::from("true") } else { conditions.join(" AND ") };
+        let result = rule.result.to_formatted_tokens();
+        lines.push(format!("IF {conditions} THEN {result} (support: {}, confidence: {:.3})", rule.support, rule.confidence));
+    }
+    return lines.join("\n");
+}
+
+/// Renders the counts of a leaf as `result = count` pairs.
+pub(crate) fn format_counts<R: ToFormattedTokens>(counts: &[(R, usize)]) -> String {
+    let counts: Vec<String> = counts.iter().map(|(r, c)| format!("{} = {c}", r.to_formatted_tokens())).collect();
+    return counts.join(", ");
+}
+
+/// Rewrites a type name given by `std::any::type_name` into a path usable in code,
+/// replacing the `core` and `alloc` crates and private modules of the standard library.
+pub(crate) fn type_path(type_name: &str) -> String {
+    let mut result = String::with_capacity(type_name.len());
+    let mut rest = type_name;
+    while !rest.is_empty() {
+        let at_path_start = !result.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == ':');
+        if at_path_start {
+            if let Some(stripped) = rest.strip_prefix("core::").or_else(|| rest.strip_prefix("alloc::")) {
+                result.push_str("std::");
+                rest = stripped;
+                continue;
+            }
+        }
+
+        let c = rest.chars().next().unwrap();
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    return result.replace("std::num::nonzero::", "std::num::");
+}
+
+/// Builds the tree and generates its code in one step.
+pub trait BuildCode {
+    fn build<T, R: ToFormattedTokens + Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        data: &mut [(T, R)],
+    ) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+        T::Decision: ToCondition;
+}
+
+impl BuildCode for TreeBuilder {
+    fn build<T, R: ToFormattedTokens + Copy + Eq + Hash + MaybeSend + MaybeSync>(
+        &self,
+        data: &mut [(T, R)],
+    ) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+        T::Decision: ToCondition,
+    {
+        return CodeGenerator::default().generate(&self.try_train(data)?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::num::NonZeroU32;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use decision_tree_builder_core::{BranchBuilder, MaybeSend, MaybeSync, TrainError, TreeBuilder};
+    use quote::quote;
+
+    use super::{type_path, TreeBuilderError};
+    use crate::testing::{assert_code_matches_tree, assert_compiles, assert_generated_matches_tree};
+    use decision_tree_builder_core::{BoolDecision, DecisionEval, OrdDecision, ThresholdPlacement, Tree, Tuple2Decision};
+
+    use crate::{format_explanation, format_rules, simplify_rules, BuildCode, CodeGenerator, ToCondition, ToFormattedTokens};
+
+    fn assert_tree_compiles<T>(data: &mut [(T, i32)])
+    where
+        T: BranchBuilder + MaybeSend + MaybeSync,
+        T::Decision: ToCondition,
+    {
+        let code = TreeBuilder::default().build(data).unwrap();
+        assert!(code.to_string().contains("if"), "no decision in {code}");
+        assert_compiles(&code, "");
+    }
+
+    #[test]
+    fn test_bool() {
+        let mut data = [(true, 1), (false, 2)];
+        let decision = TreeBuilder::default().build(&mut data).unwrap();
+        let expected = quote!(
+            pub fn decide(val: &bool) -> i32 {
+                return if (*val) { 1 } else { 2 };
+            }
+        );
+        assert_eq!(decision.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_no_data() {
+        let mut data: [(bool, i32); 0] = [];
+        let error = TreeBuilder::default().build(&mut data).unwrap_err();
+        assert!(matches!(error, TreeBuilderError::Train(TrainError::NoData)), "{error}");
+    }
+
+    #[test]
+    fn test_conflicted_leaf() {
+        let mut data = [(true, 1), (true, 2), (true, 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let generator = CodeGenerator { show_conflicted_leaves: true, ..CodeGenerator::default() };
+        let decision = generator.generate(&tree).unwrap();
+        let expected = "# [tree_builder_conflicted_leaf (1 = 1)] # [tree_builder_conflicted_leaf (2 = 2)] 2";
+        assert!(decision.to_string().contains(expected));
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut data = [((0, true), 1), ((1, true), 2), ((2, false), 3), ((2, false), 4)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let explanation = format_explanation(&tree.explain(&(1, true)));
+        assert_eq!(explanation, "val.0 < 1: false\nval.1: true\nresult: 2 (2 = 1)");
+        let explanation = format_explanation(&tree.explain(&(2, false)));
+        assert_eq!(explanation, "val.0 < 1: false\nval.1: false\nresult: 3 (3 = 1, 4 = 1)");
+
+        let code = CodeGenerator { explain: true, ..CodeGenerator::default() }.generate(&tree).unwrap();
+        let expected = quote!(
+            pub fn decide_explain(val: &(i32, bool)) -> (i32, Vec<(&'static str, bool)>, Vec<(i32, usize)>) {
+                #[allow(unused_mut)]
+                let mut path = Vec::new();
+                return if {
+                    let matched = val.0 < 1;
+                    path.push(("val.0 < 1", matched));
+                    matched
+                } {
+                    (1, path, vec![(1, 1)])
+                } else {
+                    if {
+                        let matched = val.1;
+                        path.push(("val.1", matched));
+                        matched
+                    } {
+                        (2, path, vec![(2, 1)])
+                    } else {
+                        (3, path, vec![(3, 1), (4, 1)])
+                    }
+                };
+            }
+        );
+        assert!(code.to_string().contains(&expected.to_string()), "{code}");
+        assert_compiles(&code, "");
+
+        let code = CodeGenerator { explain: true, ..CodeGenerator::default() }.generate(&Tree::<bool, i32>::Leaf {
+            result: 1,
+            counts: vec![(1, 2)],
+        });
+        assert_compiles(&code.unwrap(), "");
+    }
+
+    #[test]
+    fn test_rules() {
+        let mut data = [((0, true), 1), ((1, true), 2), ((2, false), 3), ((2, false), 4)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let expected = "\
+IF val.0 < 1 THEN 1 (support: 1, confidence: 1.000)
+IF NOT (val.0 < 1) AND val.1 THEN 2 (support: 1, confidence: 1.000)
+IF NOT (val.0 < 1) AND NOT (val.1) THEN 3 (support: 2, confidence: 0.500)";
+        assert_eq!(format_rules(&tree.rules()), expected);
+
+        let rules = simplify_rules(tree.rules(), &data, 0.25);
+        let expected = "\
+IF val.0 < 1 THEN 1 (support: 1, confidence: 1.000)
+IF NOT (val.0 < 1) AND val.1 THEN 2 (support: 1, confidence: 1.000)
+IF NOT (val.1) THEN 3 (support: 2, confidence: 0.500)
+IF true THEN 1 (support: 0, confidence: 0.000)";
+        assert_eq!(format_rules(&rules), expected);
+    }
+
+    #[test]
+    fn test_deduplicate_subtrees() {
+        let decision_eval = || DecisionEval { gain_ratio: 1.0, max_branch_width: 1 };
+        let leaf = |result| Box::new(Tree::Leaf { result, counts: vec![(result, 1)] });
+        let a = |threshold, true_branch, false_branch| {
+            let decision = Tuple2Decision::A(OrdDecision { decision_eval: decision_eval(), threshold, inclusive: false });
+            return Box::new(Tree::<(u32, bool), i32>::Branch { decision, true_branch, false_branch });
+        };
+        let b = || {
+            let decision = Tuple2Decision::B(BoolDecision { decision_eval: decision_eval() });
+            return Box::new(Tree::Branch { decision, true_branch: leaf(1), false_branch: leaf(2) });
+        };
+        let tree = a(5, b(), a(8, leaf(3), b()));
+
+        let code = CodeGenerator { deduplicate_subtrees: true, ..CodeGenerator::default() }.generate(&tree).unwrap();
+        let expected = quote!(
+            pub fn decide(val: &(u32, bool)) -> i32 {
+                return if val.0 < 5 {
+                    decide_subtree_0(val)
+                } else {
+                    if val.0 < 8 { 3 } else { decide_subtree_0(val) }
+                };
+            }
+
+            fn decide_subtree_0(val: &(u32, bool)) -> i32 {
+                return if val.1 { 1 } else { 2 };
+            }
+        );
+        assert_eq!(code.to_string(), expected.to_string());
+
+        let data: Vec<((u32, bool), i32)> = (0..20).map(|i| ((i / 2, i % 2 == 0), 0)).collect();
+        assert_code_matches_tree(&code, &tree, &data, "", |(a, b)| quote!((#a, #b)));
+    }
+
+    #[test]
+    fn test_type_path() {
+        assert_eq!(type_path("alloc::vec::Vec<alloc::string::String>"), "std::vec::Vec<std::string::String>");
+        assert_eq!(type_path("(core::num::nonzero::NonZero<u32>, &str)"), "(std::num::NonZero<u32>, &str)");
+        assert_eq!(type_path("my_core::Data<mycrate::alloc::Value>"), "my_core::Data<mycrate::alloc::Value>");
+    }
+
+    #[test]
+    fn test_compiles() {
+        assert_tree_compiles(&mut [(true, 1), (false, 2)]);
+        assert_tree_compiles(&mut [(1u32, 1), (5, 2)]);
+        assert_tree_compiles(&mut [(1.0f64, 1), (2.5, 2)]);
+        assert_tree_compiles(&mut [('a', 1), ('1', 2)]);
+        assert_tree_compiles(&mut [('p', 1), ('x', 2)]);
+        assert_tree_compiles(&mut [(Duration::from_millis(5), 1), (Duration::from_secs(1), 2)]);
+        assert_tree_compiles(&mut [(NonZeroU32::new(1).unwrap(), 1), (NonZeroU32::new(7).unwrap(), 2)]);
+    }
+
+    #[test]
+    fn test_infinite_thresholds() {
+        for threshold_placement in [ThresholdPlacement::Lower, ThresholdPlacement::Upper, ThresholdPlacement::Midpoint] {
+            let builder = TreeBuilder { threshold_placement, ..TreeBuilder::default() };
+            let mut data = [(f64::NEG_INFINITY, 1), (0.0, 2), (1.0, 2), (f64::INFINITY, 3)];
+            let code = builder.build(&mut data).unwrap();
+            if threshold_placement != ThresholdPlacement::Midpoint {
+                assert!(code.to_string().contains("INFINITY"), "{code}");
+            }
+            let accuracy = assert_generated_matches_tree(&builder, &mut data, "", |val| val.to_formatted_tokens());
+            assert_eq!(accuracy, 1.0);
+        }
+    }
+
+    #[test]
+    fn test_strings_compile() {
+        assert_tree_compiles(&mut [(String::from("a"), 1), (String::from("b"), 2)]);
+        assert_tree_compiles(&mut [(String::from("/api/a"), 1), (String::from("/api/b"), 1), (String::from("/web"), 2)]);
+        assert_tree_compiles(&mut [("a", 1), ("b", 2), ("c", 2)]);
+        assert_tree_compiles(&mut [("a.rs", 1), ("b.rs", 1), ("a.md", 2), ("bb", 2)]);
+        assert_tree_compiles(&mut [(Cow::Borrowed("a"), 1), (Cow::Owned(String::from("bb")), 2)]);
+        assert_tree_compiles(&mut [(Cow::Borrowed("a"), 1), (Cow::Borrowed("b"), 2)]);
+    }
+
+    #[test]
+    fn test_nested_compile() {
+        assert_tree_compiles(&mut [((1u32, "a"), 1), ((1, "b"), 2), ((3, "a"), 2)]);
+        assert_tree_compiles(&mut [(((1u8, true), String::from("a")), 1), (((1, false), String::from("a")), 2)]);
+        assert_tree_compiles(&mut [([1u8, 2, 3], 1), ([1, 5, 3], 2)]);
+        assert_tree_compiles(&mut [([true, false], 1), ([false, false], 2)]);
+        assert_tree_compiles(&mut [(vec![1u32, 2], 1), (vec![1, 8, 4], 2), (vec![2], 2)]);
+        assert_tree_compiles(&mut [(vec!["a", "b"], 1), (vec!["c"], 2)]);
+        assert_tree_compiles(&mut [(vec![String::from("a")], 1), (vec![String::from("c")], 2), (vec![], 2)]);
+    }
+
+    #[test]
+    fn test_references_compile() {
+        let values = [1u32, 5];
+        assert_tree_compiles(&mut [(&values[0], 1), (&values[1], 2)]);
+        assert_tree_compiles(&mut [(&"a", 1), (&"b", 2)]);
+        assert_tree_compiles(&mut [(Box::new(1u32), 1), (Box::new(5), 2)]);
+        assert_tree_compiles(&mut [(Box::new((1u32, true)), 1), (Box::new((1, false)), 2)]);
+        assert_tree_compiles(&mut [(Arc::new(vec![String::from("a")]), 1), (Arc::new(vec![String::from("b")]), 2)]);
+        assert_tree_compiles(&mut [((Box::new('a'), Arc::new(2.5f32)), 1), ((Box::new('b'), Arc::new(0.5)), 2)]);
+    }
+}
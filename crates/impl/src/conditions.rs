@@ -0,0 +1,540 @@
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use decision_tree_builder_core::*;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+
+use crate::__private::auto_deref;
+use crate::ToFormattedTokens;
+
+/// Renders a decision as a condition of the generated code.
+///
+/// `var` is an expression of the type the decision was taken on, not a reference to it.
+/// Field access, method calls and indexing can go through `__private::auto_deref(var)`.
+pub trait ToCondition {
+    fn to_condition(&self, var: TokenStream) -> TokenStream;
+
+    /// Splits the condition into a field compared with a threshold, for `FlatCodeGenerator` to store
+    /// the threshold as data. `None` for decisions that are not a threshold on a field.
+    fn to_comparison(&self, _var: TokenStream) -> Option<Comparison> {
+        return None;
+    }
+}
+
+/// Condition `field < threshold`, or `field <= threshold` if `inclusive`.
+pub struct Comparison {
+    pub field: TokenStream,
+    /// `std::any::type_name` of the threshold.
+    pub threshold_type: &'static str,
+    pub threshold: TokenStream,
+    /// Value of the threshold, `None` if its type can only be rendered.
+    pub value: Option<ThresholdValue>,
+    pub inclusive: bool,
+}
+
+/// Value of a threshold, for comparing the thresholds of a field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThresholdValue {
+    Int(i128),
+    UInt(u128),
+    Float(f64),
+    Char(char),
+    Duration(Duration),
+}
+
+impl PartialOrd for ThresholdValue {
+    /// Compares values of the same kind, and integers of any sign.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return match (self, other) {
+            (ThresholdValue::Int(a), ThresholdValue::Int(b)) => a.partial_cmp(b),
+            (ThresholdValue::UInt(a), ThresholdValue::UInt(b)) => a.partial_cmp(b),
+            (ThresholdValue::Int(a), ThresholdValue::UInt(b)) => Some(u128::try_from(*a).map_or(Ordering::Less, |a| a.cmp(b))),
+            (ThresholdValue::UInt(_), ThresholdValue::Int(_)) => other.partial_cmp(self).map(Ordering::reverse),
+            (ThresholdValue::Float(a), ThresholdValue::Float(b)) => a.partial_cmp(b),
+            (ThresholdValue::Char(a), ThresholdValue::Char(b)) => a.partial_cmp(b),
+            (ThresholdValue::Duration(a), ThresholdValue::Duration(b)) => a.partial_cmp(b),
+            _ => None,
+        };
+    }
+}
+
+impl ToCondition for BoolDecision {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        return var.to_token_stream();
+    }
+}
+
+impl<T: ToTokens> ToCondition for EqDecision<T> {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        let conditions = self.vals.iter().map(|val| quote!(#var == #val));
+        return if self.vals.len() == 1 {
+            quote!(#(#conditions)*)
+        } else {
+            quote!((#(#conditions)||*))
+        };
+    }
+}
+
+impl<T: ToFormattedTokens> ToCondition for OrdDecision<T> {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        let repr = self.threshold.to_formatted_tokens();
+        return if self.inclusive {
+            quote!(#var <= #repr)
+        } else {
+            quote!(#var < #repr)
+        };
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        let threshold_type = std::any::type_name::<T>();
+        let threshold = self.threshold.to_formatted_tokens();
+        let value = self.threshold.to_threshold_value();
+        return Some(Comparison { field: var, threshold_type, threshold, value, inclusive: self.inclusive });
+    }
+}
+
+/// Renders the decision of a tuple as a condition on the element it was taken on.
+macro_rules! tuple_to_condition {
+    ($name:ident, $($t:ident $index:tt),+) => {
+        impl<$($t: ToCondition),+> ToCondition for $name<$($t),+> {
+            fn to_condition(&self, var: TokenStream) -> TokenStream {
+                return match self {
+                    $($name::$t(inner) => {
+                        let var = auto_deref(var);
+                        inner.to_condition(quote!(#var.$index))
+                    }),+
+                };
+            }
+
+            fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+                return match self {
+                    $($name::$t(inner) => {
+                        let var = auto_deref(var);
+                        inner.to_comparison(quote!(#var.$index))
+                    }),+
+                };
+            }
+        }
+    };
+}
+
+tuple_to_condition!(Tuple1Decision, A 0);
+tuple_to_condition!(Tuple2Decision, A 0, B 1);
+tuple_to_condition!(Tuple3Decision, A 0, B 1, C 2);
+tuple_to_condition!(Tuple4Decision, A 0, B 1, C 2, D 3);
+tuple_to_condition!(Tuple5Decision, A 0, B 1, C 2, D 3, E 4);
+tuple_to_condition!(Tuple6Decision, A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_to_condition!(Tuple7Decision, A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_to_condition!(Tuple8Decision, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+tuple_to_condition!(Tuple9Decision, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+tuple_to_condition!(Tuple10Decision, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+tuple_to_condition!(Tuple11Decision, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+tuple_to_condition!(Tuple12Decision, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+impl<T: ToCondition> ToCondition for ArrayDecision<T> {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        let index = Literal::usize_unsuffixed(self.index);
+        let var = auto_deref(var);
+        return self.inner_decision.to_condition(quote!(#var [ #index ]));
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        let index = Literal::usize_unsuffixed(self.index);
+        let var = auto_deref(var);
+        return self.inner_decision.to_comparison(quote!(#var [ #index ]));
+    }
+}
+
+impl ToCondition for StringDecision {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        let base = auto_deref(var.clone());
+        return match self {
+            StringDecision::Eq(eq) => eq.to_condition(var),
+            StringDecision::StartsWith { pattern, .. } => quote!(#base.starts_with(#pattern)),
+            StringDecision::EndsWith { pattern, .. } => quote!(#base.ends_with(#pattern)),
+            StringDecision::Contains { pattern, .. } => quote!(#base.contains(#pattern)),
+            StringDecision::Len(len) => len.to_condition(quote!(#base.len())),
+        };
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        let base = auto_deref(var);
+        return match self {
+            StringDecision::Len(len) => len.to_comparison(quote!(#base.len())),
+            _ => None,
+        };
+    }
+}
+
+impl ToCondition for CharDecision {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        return match self {
+            CharDecision::Ord(ord) => ord.to_condition(var),
+            CharDecision::Class { class, .. } => {
+                let var = auto_deref(var);
+                let method = format_ident!("{}", class.method_name());
+                quote!(#var.#method())
+            }
+        };
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        return match self {
+            CharDecision::Ord(ord) => ord.to_comparison(var),
+            CharDecision::Class { .. } => None,
+        };
+    }
+}
+
+impl<T: ToFormattedTokens> ToCondition for NonZeroDecision<T> {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        let var = auto_deref(var);
+        return self.inner_decision.to_condition(quote!(#var.get()));
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        let var = auto_deref(var);
+        return self.inner_decision.to_comparison(quote!(#var.get()));
+    }
+}
+
+impl<T: ToCondition> ToCondition for DerefDecision<T> {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        return self.inner_decision.to_condition(quote!((*#var)));
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        return self.inner_decision.to_comparison(quote!((*#var)));
+    }
+}
+
+impl<T: ToCondition> ToCondition for VecDecision<T> {
+    fn to_condition(&self, var: TokenStream) -> TokenStream {
+        let var = auto_deref(var);
+        return match self {
+            VecDecision::Len(len) => len.to_condition(quote!(#var.len())),
+            VecDecision::Index { index, inner_decision, .. } => {
+                let index = Literal::usize_unsuffixed(*index);
+                let condition = inner_decision.to_condition(quote!((*v)));
+                quote!(#var.get(#index).is_some_and(|v| #condition))
+            }
+            VecDecision::Any { inner_decision, .. } => {
+                let condition = inner_decision.to_condition(quote!((*v)));
+                quote!(#var.iter().any(|v| #condition))
+            }
+        };
+    }
+
+    fn to_comparison(&self, var: TokenStream) -> Option<Comparison> {
+        let var = auto_deref(var);
+        return match self {
+            VecDecision::Len(len) => len.to_comparison(quote!(#var.len())),
+            _ => None,
+        };
+    }
+}
+
+/// Renders an oblique decision as `(value as f64) * weight + ... < threshold`,
+/// `values` being the expressions of the values the weights are multiplied with.
+pub fn oblique_condition<const N: usize>(decision: &ObliqueDecision<N>, values: [TokenStream; N]) -> TokenStream {
+    let terms: Vec<TokenStream> = values
+        .into_iter()
+        .zip(decision.weights)
+        .filter(|(_, weight)| *weight != 0.0)
+        .map(|(value, weight)| {
+            let weight = weight.to_formatted_tokens();
+            return quote!((#value as f64) * #weight);
+        })
+        .collect();
+    let threshold = decision.threshold.to_formatted_tokens();
+
+    return if terms.is_empty() {
+        quote!(0.0 < #threshold)
+    } else {
+        quote!(#(#terms)+* < #threshold)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::cmp::Ordering;
+    use std::num::NonZeroU8;
+    use std::time::Duration;
+
+    use decision_tree_builder_core::{eq_implementation, ord_implementation, utils, BranchBuilder};
+    use proc_macro2::TokenStream;
+    use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+
+    use crate::*;
+
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    enum EqTestEnum {
+        A,
+        B,
+    }
+
+    eq_implementation!(EqTestEnum);
+
+    impl ToTokens for EqTestEnum {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            match self {
+                EqTestEnum::A => tokens.append(format_ident!("A")),
+                EqTestEnum::B => tokens.append(format_ident!("B")),
+            };
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum OrdTestEnum {
+        A,
+        B,
+    }
+
+    ord_implementation!(OrdTestEnum);
+
+    impl ToFormattedTokens for OrdTestEnum {
+        fn to_formatted_tokens(&self) -> TokenStream {
+            return match self {
+                OrdTestEnum::A => format_ident!("A").to_token_stream(),
+                OrdTestEnum::B => format_ident!("B").to_token_stream(),
+            };
+        }
+    }
+
+    impl PartialOrd for OrdTestEnum {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            return (*self as u8).partial_cmp(&(*other as u8)).map(Ordering::reverse);
+        }
+    }
+
+    #[test]
+    fn test_eq_enum() {
+        let data = [(EqTestEnum::A, 1), (EqTestEnum::B, 2)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val == A);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_eq_set() {
+        let data = [("a", 1), ("b", 2), ("c", 2), ("d", 1), ("a", 1), ("b", 2)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!((val == "a" || val == "d"));
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_ord_enum() {
+        let data = [(OrdTestEnum::A, 1), (OrdTestEnum::B, 2)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val < A);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_float_midpoint() {
+        let data = [(1.0f64, 1), (2.0, 1), (3.0, 2)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val < 2.5);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_lower_threshold() {
+        let builder = TreeBuilder { threshold_placement: ThresholdPlacement::Lower, ..TreeBuilder::default() };
+        let data = [(1u32, 1), (2, 1), (4, 2)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&builder), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val <= 2);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_bool() {
+        let data = [(true, true), (false, false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_bool_ref() {
+        let data = [(&true, true), (&false, false), (&false, false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| *v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_tuple() {
+        let data = [((0, 0), true), ((0, 1), false), ((1, 0), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.1 < 1);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_triple() {
+        let data = [((0, true, "a"), true), ((0, true, "b"), false), ((1, true, "a"), true)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.2 == "a");
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_recursive_tuple() {
+        let data = [(((0, 0), (true, true)), true), (((0, 1), (false, false)), false), (((1, 0), (true, true)), false), (((1, 1), (false, false)), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.1 .1);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_list() {
+        let data = [([true, true], true), ([true, false], false), ([false, true], false), ([false, false], true)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val[1]);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_vec() {
+        let data = [(vec![1u32, 2], true), (vec![2, 1, 9], true), (vec![1, 8, 4], false), (vec![2], false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.get(1).is_some_and(|v| (*v) < 8));
+        assert_eq!(condition.to_string(), expected.to_string());
+
+        let data = [(vec!["a", "b"], true), (vec!["c", "d", "b"], true), (vec!["a", "c", "d"], false), (vec![], false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.iter().any(|v| (*v) == "b"));
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_string() {
+        let data = [("/api/users", true), ("/api/items", true), ("/static/app.js", false), ("/index.html", false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.starts_with("/a"));
+        assert_eq!(condition.to_string(), expected.to_string());
+
+        let data = [("a.rs", true), ("lib.rs", true), ("a.md", false), ("rs.md", false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.ends_with("s"));
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_char() {
+        let data = [('a', true), ('B', false), ('c', true), ('D', false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.is_uppercase());
+        assert_eq!(condition.to_string(), expected.to_string());
+
+        let data = [('p', true), ('q', true), ('x', false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val < 'x');
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_nonzero() {
+        let data = [(NonZeroU8::new(1).unwrap(), true), (NonZeroU8::new(6).unwrap(), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val.get() < 6);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_duration() {
+        let data = [(Duration::from_micros(1), true), (Duration::from_micros(3), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val < std::time::Duration::from_nanos(2000));
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_deref() {
+        let data = [(Box::new(1u32), true), (Box::new(2), true), (Box::new(5), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!((*val) < 5);
+        assert_eq!(condition.to_string(), expected.to_string());
+
+        let data = [(Cow::Borrowed("a"), true), (Cow::Owned(String::from("b")), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val == "a");
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_comparison() {
+        let data = [((0, 0), true), ((0, 1), false), ((1, 0), false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let comparison = decision.to_comparison(quote!((*val))).unwrap();
+        assert_eq!(comparison.field.to_string(), quote!(val.1).to_string());
+        assert_eq!((comparison.threshold_type, comparison.threshold.to_string(), comparison.inclusive), ("i32", String::from("1"), false));
+        assert_eq!(comparison.value, Some(ThresholdValue::Int(1)));
+
+        let data = [("a", true), ("b", false)];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        assert!(decision.to_comparison(quote!(val)).is_none());
+    }
+
+    #[test]
+    fn test_oblique() {
+        let decision = ObliqueDecision {
+            decision_eval: DecisionEval { gain_ratio: 1.0, max_branch_width: 1 },
+            weights: [0.5, 0.0, -1.25],
+            threshold: 3.0,
+        };
+        let condition = oblique_condition(&decision, [quote!(val.a), quote!(val.b), quote!(val.c)]);
+        let expected = quote!((val.a as f64) * 0.5 + (val.c as f64) * -1.25 < 3.0);
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+
+    /// Using numbers form https://sefiks.com/2018/05/13/a-step-by-step-c4-5-decision-tree-example/
+    #[test]
+    fn test_example() {
+        let data = [
+            ("Weak", false),
+            ("Strong", false),
+            ("Weak", true),
+            ("Weak", true),
+            ("Weak", true),
+            ("Strong", false),
+            ("Strong", true),
+            ("Weak", false),
+            ("Weak", true),
+            ("Weak", true),
+            ("Strong", true),
+            ("Strong", true),
+            ("Weak", true),
+            ("Strong", false),
+        ];
+        let decision = BranchBuilder::find_best_decision(&FeatureSearch::new(&TreeBuilder::default()), utils::entropy(&utils::to_counts(&data)), &data[..], |v| v);
+        let condition = decision.to_condition(quote!(val));
+        let expected = quote!(val == "Weak");
+        assert_eq!(condition.to_string(), expected.to_string());
+    }
+}
@@ -0,0 +1,133 @@
+use decision_tree_builder_core::{BranchBuilder, Tree};
+
+use crate::code_generator::format_counts;
+use crate::{format_condition, ToCondition, ToFormattedTokens};
+
+/// Renders a tree as a Graphviz DOT digraph. Branches are labelled with their condition and
+/// their edges with `true` and `false`, leaves with their result, number of samples and counts.
+pub fn to_dot<T, R: ToFormattedTokens>(tree: &Tree<T, R>) -> String
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let mut lines = vec![String::from("digraph tree {"), String::from("    node [shape=box];")];
+    add_nodes(tree, &mut 0, &mut |node| {
+        let line = match node {
+            Node::Branch { id, label } => format!("    n{id} [label=\"{}\"];", escape_dot(&label)),
+            Node::Leaf { id, label } => format!("    n{id} [label=\"{}\", shape=ellipse];", escape_dot(&label)),
+            Node::Edge { from, to, matched } => format!("    n{from} -> n{to} [label=\"{matched}\"];"),
+        };
+        lines.push(line);
+    });
+    lines.push(String::from("}"));
+    return lines.join("\n") + "\n";
+}
+
+/// Renders a tree as a Mermaid flowchart, labelled like `to_dot`.
+pub fn to_mermaid<T, R: ToFormattedTokens>(tree: &Tree<T, R>) -> String
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let mut lines = vec![String::from("flowchart TD")];
+    add_nodes(tree, &mut 0, &mut |node| {
+        let line = match node {
+            Node::Branch { id, label } => format!("    n{id}[\"{}\"]", escape_mermaid(&label)),
+            Node::Leaf { id, label } => format!("    n{id}([\"{}\"])", escape_mermaid(&label)),
+            Node::Edge { from, to, matched } => format!("    n{from} -->|{matched}| n{to}"),
+        };
+        lines.push(line);
+    });
+    return lines.join("\n") + "\n";
+}
+
+/// Element of a diagram, with labels made of lines separated by `\n`.
+enum Node {
+    Branch { id: usize, label: String },
+    Leaf { id: usize, label: String },
+    Edge { from: usize, to: usize, matched: bool },
+}
+
+/// Visits the nodes of `tree` in depth-first order, numbering them from `next_id`, and returns the id of its root.
+/// Every node is visited before the edges leading to it.
+fn add_nodes<T, R: ToFormattedTokens, F>(tree: &Tree<T, R>, next_id: &mut usize, visit: &mut F) -> usize
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+    F: FnMut(Node),
+{
+    let id = *next_id;
+    *next_id += 1;
+    match tree {
+        Tree::Leaf { result, counts } => {
+            let samples: usize = counts.iter().map(|(_, count)| count).sum();
+            let label = format!("{}\n{samples} samples\n{}", result.to_formatted_tokens(), format_counts(counts));
+            visit(Node::Leaf { id, label });
+        }
+        Tree::Branch { decision, true_branch, false_branch } => {
+            let label = format_condition(decision);
+            visit(Node::Branch { id, label });
+            let true_id = add_nodes(true_branch, next_id, visit);
+            visit(Node::Edge { from: id, to: true_id, matched: true });
+            let false_id = add_nodes(false_branch, next_id, visit);
+            visit(Node::Edge { from: id, to: false_id, matched: false });
+        }
+    }
+    return id;
+}
+
+fn escape_dot(label: &str) -> String {
+    return label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+}
+
+fn escape_mermaid(label: &str) -> String {
+    let label = label.replace('#', "#35;").replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;");
+    return label.replace('\n', "<br>");
+}
+
+#[cfg(test)]
+mod tests {
+    use decision_tree_builder_core::TreeBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_dot() {
+        let mut data = [((0, true), 1), ((1, true), 2), ((2, false), 3), ((2, false), 4)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let expected = r#"digraph tree {
+    node [shape=box];
+    n0 [label="val.0 < 1"];
+    n1 [label="1\n1 samples\n1 = 1", shape=ellipse];
+    n0 -> n1 [label="true"];
+    n2 [label="val.1"];
+    n3 [label="2\n1 samples\n2 = 1", shape=ellipse];
+    n2 -> n3 [label="true"];
+    n4 [label="3\n2 samples\n3 = 1, 4 = 1", shape=ellipse];
+    n2 -> n4 [label="false"];
+    n0 -> n2 [label="false"];
+}
+"#;
+        assert_eq!(to_dot(&tree), expected);
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let mut data = [("a\"b", 1), ("c", 2)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let expected = r#"flowchart TD
+    n0["(*val) == #quot;a\#quot;b#quot;"]
+    n1(["1<br>1 samples<br>1 = 1"])
+    n0 -->|true| n1
+    n2(["2<br>1 samples<br>2 = 1"])
+    n0 -->|false| n2
+"#;
+        assert_eq!(to_mermaid(&tree), expected);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape_dot("a \"b\" \\ c\nd"), r#"a \"b\" \\ c\nd"#);
+        assert_eq!(escape_mermaid("a < \"b\" # c\nd"), "a #lt; #quot;b#quot; #35; c<br>d");
+    }
+}
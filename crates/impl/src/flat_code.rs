@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::str::FromStr;
+
+use decision_tree_builder_core::{BranchBuilder, Tree};
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::code_generator::type_path;
+use crate::{ToCondition, ToFormattedTokens, TreeBuilderError};
+
+/// Depth above which `max_branchless_depth` is ignored, the arrays having `2^depth` entries.
+pub const MAX_BRANCHLESS_DEPTH: usize = 16;
+
+/// Generates the code of a tree as `static` arrays of nodes evaluated by a loop, instead of nested `if`s,
+/// so the size of the code grows with the number of distinct fields rather than with the number of branches.
+///
+/// Each branch is stored as `((field, threshold, inclusive), true child, false child)`, children after the
+/// last branch referring to a result. Fields compared with thresholds (see `ToCondition::to_comparison`)
+/// have a `static` array of their thresholds and a single comparison in the `match` on the field index.
+/// Other conditions are fields of their own, evaluated by their whole condition.
+pub struct FlatCodeGenerator {
+    /// Evaluates trees up to this depth without branching on the path: shallower leaves are repeated to make
+    /// the tree complete, and the value goes down a fixed number of levels of an implicit binary heap.
+    /// The arrays have `2^depth` entries, so this is only used up to `MAX_BRANCHLESS_DEPTH`.
+    pub max_branchless_depth: Option<usize>,
+}
+
+impl Default for FlatCodeGenerator {
+    fn default() -> Self {
+        let max_branchless_depth = None;
+        return FlatCodeGenerator { max_branchless_depth };
+    }
+}
+
+impl FlatCodeGenerator {
+    pub fn generate<T, R: ToFormattedTokens + Copy + Eq + Hash>(&self, tree: &Tree<T, R>) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder,
+        T::Decision: ToCondition,
+    {
+        let result_type = TokenStream::from_str(std::any::type_name::<R>())?;
+        let input_type = TokenStream::from_str(&type_path(std::any::type_name::<T>()))?;
+
+        let depth = depth(tree);
+        if depth == 0 {
+            let Tree::Leaf { result, .. } = tree else { unreachable!() };
+            let result = result.to_formatted_tokens();
+            return Ok(quote!(pub fn decide(val: &#input_type) -> #result_type {
+                return #result;
+            }));
+        }
+
+        let mut index = Index::default();
+        let inner = match self.max_branchless_depth {
+            Some(max_depth) if depth <= max_depth.min(MAX_BRANCHLESS_DEPTH) => branchless(tree, depth, &mut index, &result_type),
+            _ => nodes(tree, &mut index, &result_type),
+        };
+        let condition = index.condition()?;
+        return Ok(quote!(pub fn decide(val: &#input_type) -> #result_type {
+            #condition
+            #inner
+        }));
+    }
+}
+
+fn depth<T: BranchBuilder, R>(tree: &Tree<T, R>) -> usize {
+    return match tree {
+        Tree::Leaf { .. } => 0,
+        Tree::Branch { true_branch, false_branch, .. } => 1 + depth(true_branch).max(depth(false_branch)),
+    };
+}
+
+/// Field of the value tested by the branches: an expression compared with thresholds, or a whole condition.
+struct Field {
+    expr: TokenStream,
+    /// Type of the thresholds, `None` for a whole condition
+    threshold_type: Option<&'static str>,
+    thresholds: Vec<TokenStream>,
+    threshold_indices: HashMap<String, u32>,
+}
+
+/// Distinct fields and results of the tree, numbered in order of their first appearance.
+struct Index<R> {
+    fields: Vec<Field>,
+    field_indices: HashMap<String, u32>,
+    results: Vec<R>,
+    result_indices: HashMap<R, u32>,
+}
+
+impl<R> Default for Index<R> {
+    fn default() -> Self {
+        return Index { fields: vec![], field_indices: HashMap::new(), results: vec![], result_indices: HashMap::new() };
+    }
+}
+
+impl<R: ToFormattedTokens + Copy + Eq + Hash> Index<R> {
+    /// Returns the `(field, threshold, inclusive)` data of the condition of the decision.
+    fn comparison<D: ToCondition>(&mut self, decision: &D) -> (u32, u32, bool) {
+        let (expr, threshold_type, threshold, inclusive) = match decision.to_comparison(quote!((*val))) {
+            Some(comparison) => (comparison.field, Some(comparison.threshold_type), Some(comparison.threshold), comparison.inclusive),
+            None => (decision.to_condition(quote!((*val))), None, None, false),
+        };
+        // Fields of different types can't share their thresholds
+        let key = format!("{expr} {threshold_type:?}");
+        let fields = &mut self.fields;
+        let field_index = *self.field_indices.entry(key).or_insert_with(|| {
+            let threshold_indices = HashMap::new();
+            fields.push(Field { expr, threshold_type, thresholds: vec![], threshold_indices });
+            (fields.len() - 1) as u32
+        });
+
+        let field = &mut self.fields[field_index as usize];
+        let threshold_index = match threshold {
+            Some(threshold) => *field.threshold_indices.entry(threshold.to_string()).or_insert_with(|| {
+                field.thresholds.push(threshold);
+                (field.thresholds.len() - 1) as u32
+            }),
+            None => 0,
+        };
+        return (field_index, threshold_index, inclusive);
+    }
+
+    fn result(&mut self, result: &R) -> u32 {
+        let results = &mut self.results;
+        return *self.result_indices.entry(*result).or_insert_with(|| {
+            results.push(*result);
+            (results.len() - 1) as u32
+        });
+    }
+
+    /// Renders the threshold arrays and the `condition` closure evaluating the `(field, threshold, inclusive)`
+    /// data of a branch, with one arm per field.
+    fn condition(&self) -> Result<TokenStream, TreeBuilderError> {
+        let mut arrays = vec![];
+        let mut comparisons = vec![];
+        for (i, field) in self.fields.iter().enumerate() {
+            let expr = &field.expr;
+            let Some(threshold_type) = field.threshold_type else {
+                comparisons.push(expr.clone());
+                continue;
+            };
+            let threshold_type = TokenStream::from_str(&type_path(threshold_type))?;
+            let name = format_ident!("THRESHOLDS_{i}");
+            let len = Literal::usize_unsuffixed(field.thresholds.len());
+            let thresholds = &field.thresholds;
+            arrays.push(quote!(static #name: [#threshold_type; #len] = [#(#thresholds),*];));
+            comparisons.push(quote!(if inclusive {
+                #expr <= #name[threshold as usize]
+            } else {
+                #expr < #name[threshold as usize]
+            }));
+        }
+
+        let (last, rest) = comparisons.split_last().unwrap();
+        let arms = rest.iter().enumerate().map(|(i, comparison)| {
+            let i = Literal::u32_unsuffixed(i as u32);
+            return quote!(#i => #comparison,);
+        });
+        // Without thresholds, only the field is read
+        let data = if arrays.is_empty() { quote!((field, _, _)) } else { quote!((field, threshold, inclusive)) };
+        return Ok(quote!(
+            #(#arrays)*
+            let condition = |#data: (u32, u32, bool)| -> bool {
+                return match field {
+                    #(#arms)*
+                    _ => #last,
+                };
+            };
+        ));
+    }
+
+    fn results(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        return self.results.iter().map(|result| result.to_formatted_tokens());
+    }
+}
+
+/// Interpreter loop over the branches of the tree, stored in pre-order.
+fn nodes<T, R: ToFormattedTokens + Copy + Eq + Hash>(tree: &Tree<T, R>, index: &mut Index<R>, result_type: &TokenStream) -> TokenStream
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let mut nodes = vec![];
+    add_node(tree, index, &mut nodes);
+    // Children are numbered in a second pass, leaves following the branches
+    let branch_count = nodes.len() as u32;
+    let nodes = nodes.iter().map(|((field, threshold, inclusive), true_child, false_child)| {
+        let child = |child: &Child| match child {
+            Child::Branch(index) => Literal::u32_unsuffixed(*index),
+            Child::Leaf(index) => Literal::u32_unsuffixed(branch_count + index),
+        };
+        let (field, threshold) = (Literal::u32_unsuffixed(*field), Literal::u32_unsuffixed(*threshold));
+        let (true_child, false_child) = (child(true_child), child(false_child));
+        return quote!(((#field, #threshold, #inclusive), #true_child, #false_child));
+    });
+
+    let node_count = Literal::usize_unsuffixed(branch_count as usize);
+    let result_count = Literal::usize_unsuffixed(index.results.len());
+    let results = index.results();
+    let branch_count = Literal::u32_unsuffixed(branch_count);
+    return quote!(
+        static NODES: [((u32, u32, bool), u32, u32); #node_count] = [#(#nodes),*];
+        static RESULTS: [#result_type; #result_count] = [#(#results),*];
+        let mut node = 0;
+        loop {
+            let (comparison, true_child, false_child) = NODES[node];
+            let child = if condition(comparison) { true_child } else { false_child };
+            if child >= #branch_count {
+                return RESULTS[(child - #branch_count) as usize];
+            }
+            node = child as usize;
+        }
+    );
+}
+
+enum Child {
+    Branch(u32),
+    Leaf(u32),
+}
+
+/// Adds the branches of the tree in pre-order and returns the child referring to its root.
+fn add_node<T, R: ToFormattedTokens + Copy + Eq + Hash>(
+    tree: &Tree<T, R>,
+    index: &mut Index<R>,
+    nodes: &mut Vec<((u32, u32, bool), Child, Child)>,
+) -> Child
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    return match tree {
+        Tree::Leaf { result, .. } => Child::Leaf(index.result(result)),
+        Tree::Branch { decision, true_branch, false_branch } => {
+            let node = nodes.len();
+            nodes.push((index.comparison(decision), Child::Leaf(0), Child::Leaf(0)));
+            let true_child = add_node(true_branch, index, nodes);
+            let false_child = add_node(false_branch, index, nodes);
+            nodes[node].1 = true_child;
+            nodes[node].2 = false_child;
+            Child::Branch(node as u32)
+        }
+    };
+}
+
+/// Evaluation of the tree completed to the given depth, stored as an implicit binary heap:
+/// the children of the branch `i` are `2 * i + 1` if its condition matches and `2 * i + 2` otherwise.
+fn branchless<T, R: ToFormattedTokens + Copy + Eq + Hash>(tree: &Tree<T, R>, depth: usize, index: &mut Index<R>, result_type: &TokenStream) -> TokenStream
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let mut heap_conditions = vec![(0, 0, false); (1 << depth) - 1];
+    let mut heap_results = vec![0; 1 << depth];
+    fill_heap(tree, 0, depth, index, &mut heap_conditions, &mut heap_results);
+
+    let condition_count = Literal::usize_unsuffixed(heap_conditions.len());
+    let result_count = Literal::usize_unsuffixed(heap_results.len());
+    let heap_conditions = heap_conditions.into_iter().map(|(field, threshold, inclusive)| {
+        let (field, threshold) = (Literal::u32_unsuffixed(field), Literal::u32_unsuffixed(threshold));
+        return quote!((#field, #threshold, #inclusive));
+    });
+    let heap_results = heap_results.into_iter().map(|result| index.results[result as usize].to_formatted_tokens());
+    let depth = Literal::usize_unsuffixed(depth);
+    return quote!(
+        static CONDITIONS: [(u32, u32, bool); #condition_count] = [#(#heap_conditions),*];
+        static RESULTS: [#result_type; #result_count] = [#(#heap_results),*];
+        let mut node = 0;
+        for _ in 0..#depth {
+            node = 2 * node + 2 - condition(CONDITIONS[node]) as usize;
+        }
+        return RESULTS[node - #condition_count];
+    );
+}
+
+/// Fills the heap below `node` with `levels` levels of the tree, leaves being repeated
+/// below branches using the first field.
+fn fill_heap<T, R: ToFormattedTokens + Copy + Eq + Hash>(
+    tree: &Tree<T, R>,
+    node: usize,
+    levels: usize,
+    index: &mut Index<R>,
+    heap_conditions: &mut [(u32, u32, bool)],
+    heap_results: &mut [u32],
+) where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    if levels == 0 {
+        let Tree::Leaf { result, .. } = tree else { unreachable!("the tree is deeper than the heap") };
+        heap_results[node - heap_conditions.len()] = index.result(result);
+        return;
+    }
+    let (true_branch, false_branch) = match tree {
+        Tree::Leaf { .. } => (tree, tree),
+        Tree::Branch { decision, true_branch, false_branch } => {
+            heap_conditions[node] = index.comparison(decision);
+            (true_branch.as_ref(), false_branch.as_ref())
+        }
+    };
+    fill_heap(true_branch, 2 * node + 1, levels - 1, index, heap_conditions, heap_results);
+    fill_heap(false_branch, 2 * node + 2, levels - 1, index, heap_conditions, heap_results);
+}
+
+#[cfg(test)]
+mod tests {
+    use decision_tree_builder_core::{DecisionEval, OrdDecision, TreeBuilder};
+
+    use super::*;
+    use crate::testing::assert_code_matches_tree;
+
+    fn data() -> Vec<((u32, bool), i32)> {
+        return (0..40).map(|i| ((i % 10, i % 4 < 2), if i % 10 < 3 { 1 } else if i % 4 < 2 { 2 } else { 3 })).collect();
+    }
+
+    #[test]
+    fn test_nodes() {
+        let mut data = data();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = FlatCodeGenerator::default().generate(&tree).unwrap();
+        let expected = quote!(
+            pub fn decide(val: &(u32, bool)) -> i32 {
+                static THRESHOLDS_0: [u32; 1] = [3];
+                let condition = |(field, threshold, inclusive): (u32, u32, bool)| -> bool {
+                    return match field {
+                        0 => if inclusive {
+                            val.0 <= THRESHOLDS_0[threshold as usize]
+                        } else {
+                            val.0 < THRESHOLDS_0[threshold as usize]
+                        },
+                        _ => val.1,
+                    };
+                };
+                static NODES: [((u32, u32, bool), u32, u32); 2] = [((0, 0, false), 2, 1), ((1, 0, false), 3, 4)];
+                static RESULTS: [i32; 3] = [1, 2, 3];
+                let mut node = 0;
+                loop {
+                    let (comparison, true_child, false_child) = NODES[node];
+                    let child = if condition(comparison) { true_child } else { false_child };
+                    if child >= 2 {
+                        return RESULTS[(child - 2) as usize];
+                    }
+                    node = child as usize;
+                }
+            }
+        );
+        assert_eq!(code.to_string(), expected.to_string());
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |(a, b)| quote!((#a, #b))), 1.0);
+    }
+
+    #[test]
+    fn test_branchless() {
+        let mut data = data();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = FlatCodeGenerator { max_branchless_depth: Some(2) }.generate(&tree).unwrap();
+        // The leaf of `val.0 < 3` is repeated below a padding branch
+        let conditions = "static CONDITIONS : [(u32 , u32 , bool) ; 3] = [(0 , 0 , false) , (0 , 0 , false) , (1 , 0 , false)]";
+        assert!(code.to_string().contains(conditions), "{code}");
+        assert!(code.to_string().contains("static RESULTS : [i32 ; 4] = [1 , 1 , 2 , 3]"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |(a, b)| quote!((#a, #b))), 1.0);
+
+        let code = FlatCodeGenerator { max_branchless_depth: Some(1) }.generate(&tree).unwrap();
+        assert!(code.to_string().contains("static NODES"));
+    }
+
+    /// Tree testing `val < 1`, `val < 2`, ... up to `val < depth`.
+    fn chain(threshold: u32, depth: u32) -> Tree<u32, u32> {
+        if threshold > depth {
+            return Tree::Leaf { result: 0, counts: vec![(0, 1)] };
+        }
+        let decision_eval = DecisionEval { gain_ratio: 1.0, max_branch_width: 1 };
+        let decision = OrdDecision { decision_eval, threshold, inclusive: false };
+        let true_branch = Box::new(Tree::Leaf { result: threshold, counts: vec![(threshold, 1)] });
+        return Tree::Branch { decision, true_branch, false_branch: Box::new(chain(threshold + 1, depth)) };
+    }
+
+    #[test]
+    fn test_max_branchless_depth() {
+        let tree = chain(1, MAX_BRANCHLESS_DEPTH as u32 + 1);
+        let code = FlatCodeGenerator { max_branchless_depth: Some(usize::MAX) }.generate(&tree).unwrap();
+        assert!(code.to_string().contains("static NODES"));
+
+        let tree = chain(1, MAX_BRANCHLESS_DEPTH as u32);
+        let code = FlatCodeGenerator { max_branchless_depth: Some(usize::MAX) }.generate(&tree).unwrap();
+        assert!(code.to_string().contains("static CONDITIONS"));
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        let mut data: Vec<(u32, u32)> = (0..2000).map(|i| (i, (i * 7919) % 13)).collect();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = FlatCodeGenerator::default().generate(&tree).unwrap();
+        // Hundreds of branches on a single field are evaluated by a single comparison
+        let code_string = code.to_string();
+        let node_count: usize = code_string.split("static NODES : [((u32 , u32 , bool) , u32 , u32) ; ").nth(1).unwrap().split(']').next().unwrap().parse().unwrap();
+        assert!(node_count >= 100, "{node_count}");
+        assert_eq!(code_string.split("=>").count(), 2, "{code}");
+        let accuracy = data[..200].iter().filter(|(val, result)| tree.predict(val) == *result).count() as f64 / 200.0;
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data[..200], "", |val| quote!(#val)), accuracy);
+    }
+
+    #[test]
+    fn test_leaf() {
+        let tree = Tree::<bool, &str>::Leaf { result: "a", counts: vec![("a", 1)] };
+        let code = FlatCodeGenerator::default().generate(&tree).unwrap();
+        assert_eq!(code.to_string(), quote!(pub fn decide(val: &bool) -> &str { return "a"; }).to_string());
+    }
+}
@@ -0,0 +1,405 @@
+use std::str::FromStr;
+
+use decision_tree_builder_core::{BranchBuilder, EqDecision, OrdDecision, Tree};
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, ToTokens};
+
+use crate::code_generator::type_path;
+use crate::{ToFormattedTokens, TreeBuilderError};
+
+/// Integer types whose trees `IntegerCodeGenerator` can generate as a `match` or a lookup table.
+///
+/// Values are mapped to a `u128` key in the same order, so intervals of every integer type,
+/// including `u128` and `i128`, are computed in a single type.
+pub trait IntegerInput: BranchBuilder<Decision = OrdDecision<Self>> + Copy + ToFormattedTokens {
+    fn to_key(self) -> u128;
+    fn from_key(key: u128) -> Self;
+    const MIN_KEY: u128;
+    const MAX_KEY: u128;
+    /// Renders the `usize` index of `var` in a table starting at `min`, `var` being at least `min`
+    /// and less than `usize::MAX` above it.
+    fn table_index(var: TokenStream, min: Self) -> TokenStream;
+}
+
+/// Implements `IntegerInput`, computing indices in a type holding the difference of any two values of the table.
+/// Signed values are mapped to keys by flipping their sign bit.
+macro_rules! integer_input {
+    ($($t:ident => $wide:ident, $offset:expr;)*) => ($(
+        impl IntegerInput for $t {
+            fn to_key(self) -> u128 {
+                return (self as i128 as u128) ^ $offset;
+            }
+
+            fn from_key(key: u128) -> Self {
+                return (key ^ $offset) as $t;
+            }
+
+            const MIN_KEY: u128 = ($t::MIN as i128 as u128) ^ $offset;
+            const MAX_KEY: u128 = ($t::MAX as i128 as u128) ^ $offset;
+
+            fn table_index(var: TokenStream, min: Self) -> TokenStream {
+                let min = min.to_formatted_tokens();
+                return quote!((#var as $wide - #min as $wide) as usize);
+            }
+        }
+    )*)
+}
+
+const SIGN_BIT: u128 = 1 << 127;
+
+integer_input! {
+    u8 => u8, 0;
+    u16 => u16, 0;
+    u32 => u32, 0;
+    u64 => u64, 0;
+    u128 => u128, 0;
+    usize => usize, 0;
+    i8 => i16, SIGN_BIT;
+    i16 => i32, SIGN_BIT;
+    i32 => i64, SIGN_BIT;
+    i64 => i128, SIGN_BIT;
+    i128 => i128, SIGN_BIT;
+    isize => i128, SIGN_BIT;
+}
+
+/// Form of the code generated by `IntegerCodeGenerator`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerCode {
+    /// A `match` with a range pattern for each interval of values with the same result.
+    Match,
+    /// A `static` table of the results of the values between the first and the last threshold,
+    /// the values outside of them being decided by comparisons. Generating fails with
+    /// `TreeBuilderError::TableTooLong` if the table has more than `max_table_len` entries.
+    LookupTable,
+    /// `LookupTable` if the table has at most `max_table_len` entries, `Match` otherwise.
+    Auto,
+}
+
+/// Generates the code of trees over a single integer as a `match` or a lookup table instead of nested `if`s.
+/// Trees with less than three intervals of values with the same result are always generated as a `match`.
+/// Trees over a single enum are generated as a `match` on its variants by `generate_enum`.
+pub struct IntegerCodeGenerator {
+    pub code: IntegerCode,
+    pub max_table_len: usize,
+}
+
+impl Default for IntegerCodeGenerator {
+    fn default() -> Self {
+        let code = IntegerCode::Auto;
+        let max_table_len = 256;
+        return IntegerCodeGenerator { code, max_table_len };
+    }
+}
+
+impl IntegerCodeGenerator {
+    pub fn generate<T: IntegerInput, R: ToFormattedTokens + Copy + Eq>(
+        &self,
+        tree: &Tree<T, R>,
+    ) -> Result<TokenStream, TreeBuilderError> {
+        let result_type = TokenStream::from_str(std::any::type_name::<R>())?;
+        let input_type = TokenStream::from_str(std::any::type_name::<T>())?;
+
+        let mut intervals = vec![];
+        add_intervals(tree, T::MIN_KEY, T::MAX_KEY, &mut intervals);
+
+        // `None` inside if the table could not even be indexed by a `usize`
+        let table_len = match intervals.len() {
+            0..=2 => None,
+            len => Some(usize::try_from(intervals[len - 1].0 - intervals[1].0).ok()),
+        };
+        let max_table_len = self.max_table_len;
+        let use_table = match (self.code, table_len) {
+            (_, None) | (IntegerCode::Match, _) => None,
+            (_, Some(Some(len))) if len <= max_table_len => Some(len),
+            (IntegerCode::Auto, Some(_)) => None,
+            (IntegerCode::LookupTable, Some(len)) => return Err(TreeBuilderError::TableTooLong { len, max_table_len }),
+        };
+
+        let inner = match use_table {
+            Some(len) => table::<T, R>(&intervals, len, &result_type),
+            None => match_arms::<T, R>(&intervals),
+        };
+        return Ok(quote!(pub fn decide(val: &#input_type) -> #result_type {
+            #inner
+        }));
+    }
+
+    /// Generates the code of a tree over a single value compared by equality, such as an enum implementing
+    /// `BranchBuilder` with `eq_implementation!`, as a `match` with an arm listing the values of each result.
+    /// Values not tested by the tree take the wildcard arm. `code` and `max_table_len` are not used.
+    pub fn generate_enum<T, R: ToFormattedTokens + Copy + Eq>(&self, tree: &Tree<T, R>) -> Result<TokenStream, TreeBuilderError>
+    where
+        T: BranchBuilder<Decision = EqDecision<T>> + ToTokens + PartialEq,
+    {
+        let result_type = TokenStream::from_str(std::any::type_name::<R>())?;
+        let input_type = TokenStream::from_str(&type_path(std::any::type_name::<T>()))?;
+
+        let mut arms = vec![];
+        let mut other = None;
+        add_value_arms(tree, None, &mut vec![], &mut arms, &mut other);
+        let other = other.expect("the path never matching a decision ends in a leaf");
+
+        // Values with the result of the wildcard arm are left to it, as are unreachable leaves
+        let arms = arms.iter().filter(|(result, vals)| *result != other && !vals.is_empty()).map(|(result, vals)| {
+            let result = result.to_formatted_tokens();
+            return quote!(#(#vals)|* => #result,);
+        });
+        let other = other.to_formatted_tokens();
+        return Ok(quote!(pub fn decide(val: &#input_type) -> #result_type {
+            return match *val {
+                #(#arms)*
+                _ => #other,
+            };
+        }));
+    }
+}
+
+/// Adds the values reaching each leaf to the arm of its result, `included` being the values matching all the
+/// decisions matched on the path and `excluded` the values of the decisions not matched. The result of the leaf
+/// reached without matching any decision, which all other values reach, is set to `other`.
+fn add_value_arms<'a, T, R: Copy + Eq>(
+    tree: &'a Tree<T, R>,
+    included: Option<Vec<&'a T>>,
+    excluded: &mut Vec<&'a T>,
+    arms: &mut Vec<(R, Vec<&'a T>)>,
+    other: &mut Option<R>,
+) where
+    T: BranchBuilder<Decision = EqDecision<T>> + PartialEq,
+{
+    match tree {
+        Tree::Leaf { result, .. } => {
+            let Some(included) = included else {
+                *other = Some(*result);
+                return;
+            };
+            let vals = included.into_iter().filter(|val| !excluded.contains(val));
+            match arms.iter_mut().find(|(arm_result, _)| arm_result == result) {
+                Some((_, arm_vals)) => arm_vals.extend(vals),
+                None => arms.push((*result, vals.collect())),
+            }
+        }
+        Tree::Branch { decision, true_branch, false_branch } => {
+            let matched = match &included {
+                Some(included) => included.iter().copied().filter(|val| decision.vals.contains(val)).collect(),
+                None => decision.vals.iter().collect(),
+            };
+            add_value_arms(true_branch, Some(matched), excluded, arms, other);
+            let len = excluded.len();
+            excluded.extend(&decision.vals);
+            add_value_arms(false_branch, included, excluded, arms, other);
+            excluded.truncate(len);
+        }
+    }
+}
+
+/// Adds the intervals of the values from `min` to `max` with the same result, merging neighbouring
+/// intervals with equal results.
+fn add_intervals<T: IntegerInput, R: Copy + Eq>(tree: &Tree<T, R>, min: u128, max: u128, intervals: &mut Vec<(u128, u128, R)>) {
+    if min > max {
+        return;
+    }
+    match tree {
+        Tree::Leaf { result, .. } => match intervals.last_mut() {
+            Some((_, last_max, last_result)) if last_result == result => *last_max = max,
+            _ => intervals.push((min, max, *result)),
+        },
+        Tree::Branch { decision, true_branch, false_branch } => {
+            // First value of the false branch, `None` if the true branch takes all values
+            let threshold = decision.threshold.to_key();
+            let split = if decision.inclusive { threshold.checked_add(1) } else { Some(threshold) };
+            let Some(split) = split else {
+                add_intervals(true_branch, min, max, intervals);
+                return;
+            };
+            if split > 0 {
+                add_intervals(true_branch, min, max.min(split - 1), intervals);
+            }
+            add_intervals(false_branch, min.max(split), max, intervals);
+        }
+    }
+}
+
+fn match_arms<T: IntegerInput, R: ToFormattedTokens>(intervals: &[(u128, u128, R)]) -> TokenStream {
+    let (_, _, last) = intervals.last().unwrap();
+    let last = last.to_formatted_tokens();
+    let arms = intervals[..intervals.len() - 1].iter().enumerate().map(|(i, (min, max, result))| {
+        let max = T::from_key(*max).to_formatted_tokens();
+        let result = result.to_formatted_tokens();
+        // The first interval starts at the minimum of the type
+        return if i == 0 {
+            quote!(..=#max => #result,)
+        } else {
+            let min = T::from_key(*min).to_formatted_tokens();
+            quote!(#min..=#max => #result,)
+        };
+    });
+    return quote!(return match *val {
+        #(#arms)*
+        _ => #last,
+    };);
+}
+
+fn table<T: IntegerInput, R: ToFormattedTokens>(
+    intervals: &[(u128, u128, R)],
+    len: usize,
+    result_type: &TokenStream,
+) -> TokenStream {
+    let (first, rest) = intervals.split_first().unwrap();
+    let (last, inner) = rest.split_last().unwrap();
+
+    let entries = inner.iter().flat_map(|(min, max, result)| (*min..=*max).map(|_| result.to_formatted_tokens()));
+    let len = Literal::usize_unsuffixed(len);
+    let start = T::from_key(inner[0].0);
+    let index = T::table_index(quote!(val), start);
+    let start = start.to_formatted_tokens();
+    let end = T::from_key(last.0).to_formatted_tokens();
+    let first = first.2.to_formatted_tokens();
+    let last = last.2.to_formatted_tokens();
+    return quote!(
+        static TABLE: [#result_type; #len] = [#(#entries),*];
+        let val = *val;
+        return if val < #start {
+            #first
+        } else if val < #end {
+            TABLE[#index]
+        } else {
+            #last
+        };
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use decision_tree_builder_core::{eq_implementation, TreeBuilder};
+
+    use super::*;
+    use crate::testing::assert_code_matches_tree;
+
+    fn generator(code: IntegerCode) -> IntegerCodeGenerator {
+        return IntegerCodeGenerator { code, ..IntegerCodeGenerator::default() };
+    }
+
+    #[test]
+    fn test_match() {
+        let mut data: Vec<(u8, i32)> = (0..=255).map(|i| (i, if i < 10 { 1 } else if i < 20 { 2 } else { 1 })).collect();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = generator(IntegerCode::Match).generate(&tree).unwrap();
+        let expected = quote!(
+            pub fn decide(val: &u8) -> i32 {
+                return match *val {
+                    ..=9 => 1,
+                    10..=19 => 2,
+                    _ => 1,
+                };
+            }
+        );
+        assert_eq!(code.to_string(), expected.to_string());
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+    }
+
+    #[test]
+    fn test_lookup_table() {
+        let mut data: Vec<(i8, bool)> = (-128..=127).map(|i| (i, (i / 8) % 2 == 0 && i > -90 && i < 100)).collect();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = generator(IntegerCode::LookupTable).generate(&tree).unwrap();
+        assert!(code.to_string().contains("static TABLE"));
+        assert!(code.to_string().contains("(val as i16 - - 87 as i16) as usize"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+    }
+
+    #[test]
+    fn test_auto() {
+        let mut data = [(0u32, 1), (10, 2), (20, 3), (30, 1)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = IntegerCodeGenerator::default().generate(&tree).unwrap();
+        assert!(code.to_string().contains("static TABLE : [i32 ; 20]"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+
+        let mut data = [(0u32, 1), (1000, 2), (2000, 3), (3000, 1)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = IntegerCodeGenerator::default().generate(&tree).unwrap();
+        assert!(code.to_string().contains("match"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+    }
+
+    #[test]
+    fn test_table_too_long() {
+        let mut data = [(0u64, 1), (1 << 40, 2), (1 << 50, 3), (1 << 60, 1)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let error = generator(IntegerCode::LookupTable).generate(&tree).unwrap_err();
+        assert!(matches!(error, TreeBuilderError::TableTooLong { len: Some(_), max_table_len: 256 }), "{error}");
+
+        let code = IntegerCodeGenerator::default().generate(&tree).unwrap();
+        assert!(code.to_string().contains("match"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        let mut data: Vec<(u128, i32)> = (0..40).map(|i| (u128::MAX - 40 + i, (i / 10) as i32)).collect();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = IntegerCodeGenerator::default().generate(&tree).unwrap();
+        assert!(code.to_string().contains("static TABLE : [i32 ; 20]"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+
+        let mut data: Vec<(i128, i32)> = (0..40).map(|i| (i128::MIN + i * (1 << 100), (i / 10) as i32)).collect();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = IntegerCodeGenerator::default().generate(&tree).unwrap();
+        assert!(code.to_string().contains("match"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, "", |val| quote!(#val)), 1.0);
+    }
+
+    /// Enum of `test_enum`, defined at its path in the generated code.
+    const COLOR: &str = "
+pub mod decision_tree_builder_impl {
+    pub mod integer_code {
+        pub mod tests {
+            #[derive(Clone, Copy)]
+            pub enum Color { Red, Green, Blue, Black }
+        }
+    }
+}
+use decision_tree_builder_impl::integer_code::tests::Color;
+";
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+        Black,
+    }
+
+    eq_implementation!(Color);
+
+    impl ToTokens for Color {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let variant = match self {
+                Color::Red => quote!(Red),
+                Color::Green => quote!(Green),
+                Color::Blue => quote!(Blue),
+                Color::Black => quote!(Black),
+            };
+            tokens.extend(quote!(Color::#variant));
+        }
+    }
+
+    #[test]
+    fn test_enum() {
+        let colors = [Color::Red, Color::Green, Color::Blue, Color::Black];
+        let mut data: Vec<(Color, i32)> = (0..20).map(|i| (colors[i % 4], [1, 2, 1, 3][i % 4])).collect();
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = IntegerCodeGenerator::default().generate_enum(&tree).unwrap();
+        assert!(code.to_string().contains("return match * val {"), "{code}");
+        assert!(!code.to_string().contains("if"), "{code}");
+        assert_eq!(assert_code_matches_tree(&code, &tree, &data, COLOR, |color| quote!(#color)), 1.0);
+    }
+
+    #[test]
+    fn test_leaf() {
+        let mut data = [(0i64, 1), (5, 1)];
+        let tree = TreeBuilder::default().train(&mut data);
+        let code = IntegerCodeGenerator::default().generate(&tree).unwrap();
+        assert_eq!(code.to_string(), quote!(pub fn decide(val: &i64) -> i32 { return match *val { _ => 1, }; }).to_string());
+    }
+}
@@ -1,12 +1,19 @@
-mod branch_builder;
-mod decision_eval;
-mod decisions;
+#[doc(hidden)]
+pub mod __private;
+mod code_generator;
+mod conditions;
+mod diagram;
+mod flat_code;
+mod integer_code;
+mod simplify;
+pub mod testing;
 mod token_formatter;
-mod tree_builder;
-mod utils;
 
-pub use branch_builder::implementations::*;
-pub use decision_eval::*;
-pub use decisions::*;
+pub use code_generator::*;
+pub use conditions::*;
+pub use diagram::*;
+pub use flat_code::*;
+pub use integer_code::*;
+pub use simplify::*;
+pub use decision_tree_builder_core::*;
 pub use token_formatter::*;
-pub use tree_builder::*;
@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+
+use decision_tree_builder_core::{BranchBuilder, Tree};
+use quote::quote;
+
+use crate::{ThresholdValue, ToCondition, ToFormattedTokens};
+
+/// Simplifies a tree without changing its predictions: conditions implied or contradicted by the conditions
+/// of their ancestors are removed together with the branch they make unreachable, and branches whose children
+/// generate the same code are replaced by one of them, merging the counts of their leaves.
+///
+/// Conditions are compared as generated code. Comparisons of a field with a threshold having a value
+/// (see `ToCondition::to_comparison`) are also compared through the interval of the field left by the ancestors.
+pub fn simplify_tree<T, R: ToFormattedTokens + Copy + Eq>(tree: Tree<T, R>) -> Tree<T, R>
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    return simplify(tree, &mut vec![]).0;
+}
+
+/// Condition of an ancestor with the outcome on the path.
+struct Known {
+    condition: String,
+    threshold: Option<Threshold>,
+    matched: bool,
+}
+
+/// Condition comparing a field to a threshold, `field < value` or `field <= value` if inclusive.
+struct Threshold {
+    field: String,
+    value: ThresholdValue,
+    inclusive: bool,
+}
+
+fn threshold<D: ToCondition>(decision: &D) -> Option<Threshold> {
+    let comparison = decision.to_comparison(quote!((*val)))?;
+    let field = comparison.field.to_string();
+    return Some(Threshold { field, value: comparison.value?, inclusive: comparison.inclusive });
+}
+
+/// Returns the simplified tree together with its generated code, compared for merging branches.
+fn simplify<T, R: ToFormattedTokens + Copy + Eq>(tree: Tree<T, R>, known: &mut Vec<Known>) -> (Tree<T, R>, String)
+where
+    T: BranchBuilder,
+    T::Decision: ToCondition,
+{
+    let (decision, true_branch, false_branch) = match tree {
+        Tree::Leaf { result, counts } => {
+            let code = result.to_formatted_tokens().to_string();
+            return (Tree::Leaf { result, counts }, code);
+        }
+        Tree::Branch { decision, true_branch, false_branch } => (decision, true_branch, false_branch),
+    };
+
+    let condition = decision.to_condition(quote!((*val))).to_string();
+    let threshold = threshold(&decision);
+    match implied(&condition, threshold.as_ref(), known) {
+        Some(true) => return simplify(*true_branch, known),
+        Some(false) => return simplify(*false_branch, known),
+        None => {}
+    }
+
+    known.push(Known { condition, threshold, matched: true });
+    let (true_branch, true_code) = simplify(*true_branch, known);
+    known.last_mut().unwrap().matched = false;
+    let (false_branch, false_code) = simplify(*false_branch, known);
+    let Known { condition, .. } = known.pop().unwrap();
+
+    if true_code == false_code {
+        return (merge(true_branch, false_branch), true_code);
+    }
+    let code = format!("if {condition} {{ {true_code} }} else {{ {false_code} }}");
+    return (Tree::Branch { decision, true_branch: Box::new(true_branch), false_branch: Box::new(false_branch) }, code);
+}
+
+/// Merges two trees generating the same code, adding the counts of their leaves.
+fn merge<T: BranchBuilder, R: Eq>(a: Tree<T, R>, b: Tree<T, R>) -> Tree<T, R> {
+    return match (a, b) {
+        (Tree::Leaf { result, mut counts }, Tree::Leaf { counts: other_counts, .. }) => {
+            for (other_result, other_count) in other_counts {
+                match counts.iter_mut().find(|(r, _)| *r == other_result) {
+                    Some((_, count)) => *count += other_count,
+                    None => counts.push((other_result, other_count)),
+                }
+            }
+            Tree::Leaf { result, counts }
+        }
+        (Tree::Branch { decision, true_branch, false_branch }, Tree::Branch { true_branch: other_true, false_branch: other_false, .. }) => {
+            let true_branch = Box::new(merge(*true_branch, *other_true));
+            let false_branch = Box::new(merge(*false_branch, *other_false));
+            Tree::Branch { decision, true_branch, false_branch }
+        }
+        _ => unreachable!("trees generating the same code have the same shape"),
+    };
+}
+
+/// Returns the outcome of the condition if the outcomes of the known conditions determine it.
+fn implied(condition: &str, threshold: Option<&Threshold>, known: &[Known]) -> Option<bool> {
+    if let Some(same) = known.iter().find(|known| known.condition == condition) {
+        return Some(same.matched);
+    }
+
+    let threshold = threshold?;
+    for known in known {
+        let Some(bound) = &known.threshold else { continue };
+        if bound.field != threshold.field {
+            continue;
+        }
+        let Some(order) = bound.value.partial_cmp(&threshold.value) else { continue };
+        if known.matched {
+            // The field is below the bound, which also excludes NaN
+            let below = match (bound.inclusive, threshold.inclusive) {
+                (false, _) | (true, true) => order != Ordering::Greater,
+                (true, false) => order == Ordering::Less,
+            };
+            if below {
+                return Some(true);
+            }
+        } else {
+            // The field is at least the bound, or NaN which never matches either
+            let above = match (bound.inclusive, threshold.inclusive) {
+                (false, false) | (true, _) => order != Ordering::Less,
+                (false, true) => order == Ordering::Greater,
+            };
+            if above {
+                return Some(false);
+            }
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use decision_tree_builder_core::{BoolDecision, DecisionEval, OrdDecision, Tuple2Decision};
+
+    use super::*;
+    use crate::CodeGenerator;
+
+    fn decision_eval() -> DecisionEval {
+        return DecisionEval { gain_ratio: 1.0, max_branch_width: 1 };
+    }
+
+    fn leaf<T: BranchBuilder>(result: i32, count: usize) -> Box<Tree<T, i32>> {
+        return Box::new(Tree::Leaf { result, counts: vec![(result, count)] });
+    }
+
+    fn below<T>(threshold: T, inclusive: bool, true_branch: Box<Tree<T, i32>>, false_branch: Box<Tree<T, i32>>) -> Box<Tree<T, i32>>
+    where T: BranchBuilder<Decision = OrdDecision<T>> {
+        let decision = OrdDecision { decision_eval: decision_eval(), threshold, inclusive };
+        return Box::new(Tree::Branch { decision, true_branch, false_branch });
+    }
+
+    fn code<T, R: ToFormattedTokens + Copy + Eq + std::hash::Hash>(tree: &Tree<T, R>) -> String
+    where
+        T: BranchBuilder,
+        T::Decision: ToCondition,
+    {
+        return CodeGenerator::default().generate(tree).unwrap().to_string();
+    }
+
+    #[test]
+    fn test_implied_thresholds() {
+        // `val < 3` always matches below 5 and never matches from 5 on
+        let tree = below(5u32, false, below(3, false, leaf(1, 1), leaf(2, 1)), below(3, false, leaf(3, 1), leaf(4, 1)));
+        let expected = *below(5u32, false, below(3, false, leaf(1, 1), leaf(2, 1)), leaf(4, 1));
+        assert_eq!(code(&simplify_tree(*tree)), code(&expected));
+
+        // Values from 3 on may or may not be at most 6
+        let tree = *below(3u32, false, leaf(1, 1), below(6, true, leaf(2, 1), leaf(4, 1)));
+        assert!(code(&simplify_tree(tree)).contains("<= 6"));
+
+        let tree = below(-2i32, true, below(-1, false, leaf(1, 1), leaf(2, 1)), below(-2, false, leaf(3, 1), leaf(4, 1)));
+        assert_eq!(code(&simplify_tree(*tree)), code(&*below(-2i32, true, leaf(1, 1), leaf(4, 1))));
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        // Thresholds above `i128::MAX` are compared exactly, and not as the same float
+        let tree = below(u128::MAX, false, below(u128::MAX - 1, false, leaf(1, 1), leaf(2, 1)), leaf(3, 1));
+        assert!(code(&simplify_tree(*tree)).contains(&(u128::MAX - 1).to_string()));
+
+        let tree = below(u128::MAX - 1, false, leaf(1, 1), below(u128::MAX - 2, false, leaf(2, 1), leaf(3, 1)));
+        assert_eq!(code(&simplify_tree(*tree)), code(&*below(u128::MAX - 1, false, leaf(1, 1), leaf(3, 1))));
+
+        let tree = below(i128::MIN + 1, false, below(i128::MIN, false, leaf(1, 1), leaf(2, 1)), leaf(3, 1));
+        assert!(code(&simplify_tree(*tree)).contains(&i128::MIN.to_string()[1..]));
+    }
+
+    #[test]
+    fn test_nan() {
+        // Values not below 3 may be NaN, which is not below 5 either
+        let tree = below(3.0, false, leaf(1, 1), below(5.0, false, leaf(2, 1), leaf(3, 1)));
+        assert_eq!(code(&simplify_tree(*tree)), code(&*below(3.0, false, leaf(1, 1), below(5.0, false, leaf(2, 1), leaf(3, 1)))));
+
+        let tree = below(3.0, false, below(5.0, false, leaf(1, 1), leaf(2, 1)), leaf(3, 1));
+        assert_eq!(code(&simplify_tree(*tree)), code(&*below(3.0, false, leaf(1, 1), leaf(3, 1))));
+    }
+
+    type Pair = (u32, bool);
+
+    fn a(threshold: u32, true_branch: Box<Tree<Pair, i32>>, false_branch: Box<Tree<Pair, i32>>) -> Box<Tree<Pair, i32>> {
+        let decision = Tuple2Decision::A(OrdDecision { decision_eval: decision_eval(), threshold, inclusive: false });
+        return Box::new(Tree::Branch { decision, true_branch, false_branch });
+    }
+
+    fn b(true_branch: Box<Tree<Pair, i32>>, false_branch: Box<Tree<Pair, i32>>) -> Box<Tree<Pair, i32>> {
+        let decision = Tuple2Decision::B(BoolDecision { decision_eval: decision_eval() });
+        return Box::new(Tree::Branch { decision, true_branch, false_branch });
+    }
+
+    #[test]
+    fn test_repeated_condition() {
+        let tree = b(a(5, b(leaf(1, 1), leaf(2, 1)), leaf(3, 1)), leaf(4, 1));
+        let simplified = simplify_tree(*tree);
+        assert_eq!(code(&simplified), code(&*b(a(5, leaf(1, 1), leaf(3, 1)), leaf(4, 1))));
+    }
+
+    #[test]
+    fn test_identical_branches() {
+        let tree = a(5, b(leaf(1, 1), leaf(2, 3)), b(leaf(1, 2), leaf(2, 4)));
+        let simplified = simplify_tree(*tree);
+        assert_eq!(code(&simplified), code(&*b(leaf(1, 1), leaf(2, 1))));
+        let Tree::Branch { true_branch, false_branch, .. } = simplified else { panic!("Expected a branch") };
+        assert!(matches!(*true_branch, Tree::Leaf { result: 1, ref counts } if counts == &[(1, 3)]));
+        assert!(matches!(*false_branch, Tree::Leaf { result: 2, ref counts } if counts == &[(2, 7)]));
+
+        let tree = below(5u32, false, leaf(1, 1), Box::new(Tree::Leaf { result: 1, counts: vec![(1, 2), (2, 1)] }));
+        assert!(matches!(simplify_tree(*tree), Tree::Leaf { result: 1, counts } if counts == [(1, 3), (2, 1)]));
+    }
+
+    #[test]
+    fn test_threshold() {
+        let decision = Tuple2Decision::<_, BoolDecision>::A(OrdDecision { decision_eval: decision_eval(), threshold: 5u32, inclusive: true });
+        let Threshold { field, value, inclusive } = threshold(&decision).unwrap();
+        assert_eq!((field.as_str(), value, inclusive), ("val . 0", ThresholdValue::UInt(5), true));
+
+        let decision = Tuple2Decision::<OrdDecision<u32>, _>::B(BoolDecision { decision_eval: decision_eval() });
+        assert!(threshold(&decision).is_none());
+    }
+
+    #[test]
+    fn test_implied_values() {
+        // Negative floats and durations are compared by value, not by their code
+        let tree = below(-0.5f32, false, below(-0.25, true, leaf(1, 1), leaf(2, 1)), leaf(3, 1));
+        assert_eq!(code(&simplify_tree(*tree)), code(&*below(-0.5f32, false, leaf(1, 1), leaf(3, 1))));
+
+        let tree = below(Duration::from_secs(2), false, below(Duration::from_secs(3), false, leaf(1, 1), leaf(2, 1)), leaf(3, 1));
+        assert_eq!(code(&simplify_tree(*tree)), code(&*below(Duration::from_secs(2), false, leaf(1, 1), leaf(3, 1))));
+    }
+}
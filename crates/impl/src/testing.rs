@@ -0,0 +1,152 @@
+//! Utilities for testing generated code by compiling and running it with `rustc`.
+//!
+//! `rustc` is taken from the `RUSTC` environment variable, or from `PATH` otherwise.
+
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use decision_tree_builder_core::{BranchBuilder, MaybeSend, MaybeSync, Tree, TreeBuilder};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{CodeGenerator, ToCondition, ToFormattedTokens};
+
+/// Checks that `code` compiles as a library together with `items`, which define the types it uses,
+/// panicking with the compiler output otherwise.
+pub fn assert_compiles(code: &TokenStream, items: &str) {
+    let dir = temp_dir();
+    let out_dir = dir.to_str().unwrap();
+    compile(&dir, code, &format!("{items}\n{code}\n"), &["--crate-type", "lib", "--emit", "metadata", "--out-dir", out_dir]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Trains a tree on `data` and runs the generated `decide` function on every value of `data`,
+/// compiled together with `items`, which define the types it uses. `to_expr` renders a value
+/// as an expression creating it, e.g. `quote!(Data { a: 1 })`.
+///
+/// Panics unless the generated function returns the same result as the trained tree for every value,
+/// and returns the accuracy of the generated function on `data`.
+pub fn assert_generated_matches_tree<T, R, F>(builder: &TreeBuilder, data: &mut [(T, R)], items: &str, to_expr: F) -> f64
+where
+    T: BranchBuilder + MaybeSend + MaybeSync,
+    T::Decision: ToCondition,
+    R: ToFormattedTokens + Copy + Eq + Hash + MaybeSend + MaybeSync,
+    F: Fn(&T) -> TokenStream,
+{
+    assert!(!data.is_empty(), "no data to compare the generated code on");
+
+    let tree = builder.train(data);
+    let code = CodeGenerator::default().generate(&tree).unwrap();
+    return assert_code_matches_tree(&code, &tree, data, items, to_expr);
+}
+
+/// Runs the `decide` function of `code`, generated from `tree` by any generator, on every value of `data`,
+/// like `assert_generated_matches_tree`.
+pub fn assert_code_matches_tree<T, R, F>(code: &TokenStream, tree: &Tree<T, R>, data: &[(T, R)], items: &str, to_expr: F) -> f64
+where
+    T: BranchBuilder,
+    R: ToFormattedTokens + Copy,
+    F: Fn(&T) -> TokenStream,
+{
+    assert!(!data.is_empty(), "no data to compare the generated code on");
+
+    let values = data.iter().map(|(val, _)| to_expr(val));
+    let predicted = data.iter().map(|(val, _)| tree.predict(val).to_formatted_tokens());
+    let labels = data.iter().map(|(_, label)| label.to_formatted_tokens());
+    let main = quote!(
+        fn main() {
+            let values = [#(#values),*];
+            let predicted = [#(#predicted),*];
+            let labels = [#(#labels),*];
+            for (i, value) in values.iter().enumerate() {
+                let result = decide(value);
+                println!("{} {}", result == predicted[i], result == labels[i]);
+            }
+        }
+    );
+
+    let dir = temp_dir();
+    let binary = dir.join("main");
+    compile(&dir, code, &format!("{items}\n{code}\n{main}\n"), &["-o", binary.to_str().unwrap()]);
+    let output = Command::new(&binary).output().expect("failed to run the generated code");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "generated code failed:\n{}", String::from_utf8_lossy(&output.stderr));
+
+    let mut mismatched = vec![];
+    let mut correct = 0;
+    for (i, line) in String::from_utf8(output.stdout).unwrap().lines().enumerate() {
+        let (matches_tree, matches_label) = line.split_once(' ').unwrap();
+        if matches_tree != "true" {
+            mismatched.push(i);
+        }
+        if matches_label == "true" {
+            correct += 1;
+        }
+    }
+
+    assert!(mismatched.is_empty(), "generated code disagrees with the tree on values {mismatched:?}:\n{code}");
+    return correct as f64 / data.len() as f64;
+}
+
+/// Compiles `source` in `dir` with the given arguments, panicking with the compiler output on errors.
+fn compile(dir: &Path, code: &TokenStream, source: &str, args: &[&str]) {
+    let source_path = dir.join("main.rs");
+    std::fs::write(&source_path, source).unwrap();
+
+    let output = Command::new(rustc())
+        .args(["--edition", "2021"])
+        .args(args)
+        .arg(&source_path)
+        .output()
+        .expect("failed to run rustc");
+
+    if !output.status.success() {
+        std::fs::remove_dir_all(dir).unwrap();
+        panic!("generated code does not compile:\n{code}\n\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
+
+fn rustc() -> String {
+    return std::env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+}
+
+/// Creates a new directory for the files of a single compilation.
+fn temp_dir() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let index = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("decision_tree_builder_{}_{index}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    return dir;
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn test_compiles() {
+        assert_compiles(&quote!(pub fn decide(val: &Data) -> bool { return val.a < 5; }), "pub struct Data { pub a: u32 }");
+    }
+
+    #[test]
+    fn test_generated_matches_tree() {
+        let mut data: Vec<((u32, String), i32)> =
+            (0..20).map(|i| ((i, format!("item_{}", i % 3)), if i < 10 && i % 3 != 0 { 1 } else { 2 })).collect();
+        let accuracy = assert_generated_matches_tree(&TreeBuilder::default(), &mut data, "", |(a, b)| quote!((#a, String::from(#b))));
+        assert_eq!(accuracy, 1.0);
+
+        let mut data = [(1u8, true), (2, false), (2, true), (3, true)];
+        let accuracy = assert_generated_matches_tree(&TreeBuilder::default(), &mut data, "", |val| quote!(#val));
+        assert_eq!(accuracy, 0.75);
+    }
+
+    #[test]
+    #[should_panic(expected = "generated code does not compile")]
+    fn test_does_not_compile() {
+        assert_compiles(&quote!(pub fn decide(val: &u32) -> bool { return val < 5; }), "");
+    }
+}
@@ -1,8 +1,17 @@
+use std::time::Duration;
+
 use proc_macro2::{Literal, TokenStream};
-use quote::ToTokens;
+use quote::{quote, ToTokens};
+
+use crate::ThresholdValue;
 
 pub trait ToFormattedTokens {
     fn to_formatted_tokens(&self) -> TokenStream;
+
+    /// Value compared with other thresholds of the same type, `None` for types without one.
+    fn to_threshold_value(&self) -> Option<ThresholdValue> {
+        return None;
+    }
 }
 
 impl ToFormattedTokens for &str {
@@ -12,11 +21,15 @@ impl ToFormattedTokens for &str {
 }
 
 macro_rules! literal_unsuffixed {
-    ($($name:ident => $t:ident,)*) => ($(
+    ($($name:ident => $t:ident, $value:ident,)*) => ($(
         impl ToFormattedTokens for $t {
             fn to_formatted_tokens(&self) -> TokenStream {
                 return Literal::$name(*self).to_token_stream();
             }
+
+            fn to_threshold_value(&self) -> Option<ThresholdValue> {
+                return Some(ThresholdValue::$value(*self as _));
+            }
         }
     )*)
 }
@@ -33,21 +46,90 @@ macro_rules! to_tokens {
 }
 
 literal_unsuffixed! {
-    u8_unsuffixed => u8,
-    u16_unsuffixed => u16,
-    u32_unsuffixed => u32,
-    u64_unsuffixed => u64,
-    u128_unsuffixed => u128,
-    usize_unsuffixed => usize,
-    i8_unsuffixed => i8,
-    i16_unsuffixed => i16,
-    i32_unsuffixed => i32,
-    i64_unsuffixed => i64,
-    i128_unsuffixed => i128,
-    isize_unsuffixed => isize,
+    u8_unsuffixed => u8, UInt,
+    u16_unsuffixed => u16, UInt,
+    u32_unsuffixed => u32, UInt,
+    u64_unsuffixed => u64, UInt,
+    u128_unsuffixed => u128, UInt,
+    usize_unsuffixed => usize, UInt,
+    i8_unsuffixed => i8, Int,
+    i16_unsuffixed => i16, Int,
+    i32_unsuffixed => i32, Int,
+    i64_unsuffixed => i64, Int,
+    i128_unsuffixed => i128, Int,
+    isize_unsuffixed => isize, Int,
+}
+
+/// Renders floats as literals, and values without a literal as the constants of their type.
+macro_rules! float_to_tokens {
+    ($($name:ident => $t:ident,)*) => ($(
+        impl ToFormattedTokens for $t {
+            fn to_formatted_tokens(&self) -> TokenStream {
+                return if self.is_finite() {
+                    Literal::$name(*self).to_token_stream()
+                } else if self.is_nan() {
+                    quote!($t::NAN)
+                } else if *self > 0.0 {
+                    quote!($t::INFINITY)
+                } else {
+                    quote!($t::NEG_INFINITY)
+                };
+            }
+
+            fn to_threshold_value(&self) -> Option<ThresholdValue> {
+                return Some(ThresholdValue::Float(*self as f64));
+            }
+        }
+    )*)
+}
+
+float_to_tokens! {
     f32_unsuffixed => f32,
     f64_unsuffixed => f64,
 }
 
 to_tokens!(String);
 to_tokens!(bool);
+
+impl ToFormattedTokens for char {
+    fn to_formatted_tokens(&self) -> TokenStream {
+        return self.to_token_stream();
+    }
+
+    fn to_threshold_value(&self) -> Option<ThresholdValue> {
+        return Some(ThresholdValue::Char(*self));
+    }
+}
+
+impl ToFormattedTokens for Duration {
+    fn to_formatted_tokens(&self) -> TokenStream {
+        return match u64::try_from(self.as_nanos()) {
+            Ok(nanos) => {
+                let nanos = Literal::u64_unsuffixed(nanos);
+                quote!(std::time::Duration::from_nanos(#nanos))
+            }
+            Err(_) => {
+                let secs = Literal::u64_unsuffixed(self.as_secs());
+                let nanos = Literal::u32_unsuffixed(self.subsec_nanos());
+                quote!(std::time::Duration::new(#secs, #nanos))
+            }
+        };
+    }
+
+    fn to_threshold_value(&self) -> Option<ThresholdValue> {
+        return Some(ThresholdValue::Duration(*self));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(1.5f64.to_formatted_tokens().to_string(), "1.5");
+        assert_eq!(f64::INFINITY.to_formatted_tokens().to_string(), "f64 :: INFINITY");
+        assert_eq!(f32::NEG_INFINITY.to_formatted_tokens().to_string(), "f32 :: NEG_INFINITY");
+        assert_eq!(f64::NAN.to_formatted_tokens().to_string(), "f64 :: NAN");
+    }
+}
@@ -1,9 +1,7 @@
-use std::default::Default;
+use decision_tree_builder::{BranchBuilder, ToCondition};
+use decision_tree_builder_impl::{CodeGenerator, TreeBuilder};
 
-use decision_tree_builder::BranchBuilder;
-use decision_tree_builder_impl::TreeBuilder;
-
-#[derive(BranchBuilder)]
+#[derive(BranchBuilder, ToCondition)]
 pub struct TestData {
     a: f32,
     b: f64,
@@ -16,9 +14,8 @@ fn main() {
         (TestData { a: 2.0, b: 2.0 }, false),
         (TestData { a: 3.0, b: 3.0 }, false),
     ];
-    let token_stream = TreeBuilder { show_conflicted_leaves: true, ..Default::default() }
-        .build(&mut test_data)
-        .unwrap();
+    let tree = TreeBuilder::default().train(&mut test_data);
+    let token_stream = CodeGenerator { show_conflicted_leaves: true, ..CodeGenerator::default() }.generate(&tree).unwrap();
     let generated_ast = syn::parse2(token_stream).unwrap();
     let formatted = prettyplease::unparse(&generated_ast);
     println!("{formatted}");
@@ -1,4 +1,4 @@
-use decision_tree_builder_impl::TreeBuilder;
+use decision_tree_builder_impl::{BuildCode, TreeBuilder};
 
 pub struct TestData {
     a: usize,
@@ -20,66 +20,72 @@ fn main() {
     println!("{}", formatted);
 }
 
-impl decision_tree_builder_impl::BranchBuilder for TestData {
+impl decision_tree_builder_core::BranchBuilder for TestData {
     type Decision = __TestDataDecision;
-    fn find_best_decision<R: Copy + Eq + std::hash::Hash, F, D>(entropy: f64, data: &mut [(D, R)], extract: F) -> Self::Decision
-    where F: Fn(&D) -> &Self {
-        use decision_tree_builder_impl::Decision;
-        let decisions = [
-            __TestDataDecision::a(decision_tree_builder_impl::BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).a)),
-            __TestDataDecision::b(decision_tree_builder_impl::BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).b)),
-            __TestDataDecision::c(decision_tree_builder_impl::BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).c)),
-            __TestDataDecision::d(decision_tree_builder_impl::BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).d)),
-        ];
+    fn find_best_decision<'a, R, F, D>(search: &decision_tree_builder_core::FeatureSearch, entropy: f64, data: &'a [(D, R)], extract: F) -> Self::Decision
+    where
+        R: Copy + Eq + std::hash::Hash + decision_tree_builder_core::MaybeSync,
+        F: Fn(&'a D) -> &'a Self + decision_tree_builder_core::MaybeSync,
+                Self: 'a,
+        D: decision_tree_builder_core::MaybeSync,
+    {
+        use decision_tree_builder_core::Decision;
+        let decisions = decision_tree_builder_core::parallel::map(4usize, |i| match i {
+            0usize => __TestDataDecision::Fa(decision_tree_builder_core::BranchBuilder::find_best_decision(&search.field(0usize), entropy, data, |d| &extract(d).a)),
+            1usize => __TestDataDecision::Fb(decision_tree_builder_core::BranchBuilder::find_best_decision(&search.field(1usize), entropy, data, |d| &extract(d).b)),
+            2usize => __TestDataDecision::Fc(decision_tree_builder_core::BranchBuilder::find_best_decision(&search.field(2usize), entropy, data, |d| &extract(d).c)),
+            3usize => __TestDataDecision::Fd(decision_tree_builder_core::BranchBuilder::find_best_decision(&search.field(3usize), entropy, data, |d| &extract(d).d)),
+            _ => unreachable!(),
+        });
         return decisions.into_iter().max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval())).unwrap();
     }
-    fn split_data<F, D, R>(data: &mut [(D, R)], extract: F, decision: &Self::Decision) -> usize
-    where F: Fn(&D) -> &Self {
+    fn matches(&self, decision: &Self::Decision) -> bool {
         return match decision {
-            __TestDataDecision::a(inner) => decision_tree_builder_impl::BranchBuilder::split_data(data, |d| &extract(d).a, inner),
-            __TestDataDecision::b(inner) => decision_tree_builder_impl::BranchBuilder::split_data(data, |d| &extract(d).b, inner),
-            __TestDataDecision::c(inner) => decision_tree_builder_impl::BranchBuilder::split_data(data, |d| &extract(d).c, inner),
-            __TestDataDecision::d(inner) => decision_tree_builder_impl::BranchBuilder::split_data(data, |d| &extract(d).d, inner),
+            __TestDataDecision::Fa(inner) => decision_tree_builder_core::BranchBuilder::matches(&self.a, inner),
+            __TestDataDecision::Fb(inner) => decision_tree_builder_core::BranchBuilder::matches(&self.b, inner),
+            __TestDataDecision::Fc(inner) => decision_tree_builder_core::BranchBuilder::matches(&self.c, inner),
+            __TestDataDecision::Fd(inner) => decision_tree_builder_core::BranchBuilder::matches(&self.d, inner),
         };
     }
 }
 pub enum __TestDataDecision {
-    a(<usize as decision_tree_builder_impl::BranchBuilder>::Decision),
-    b(<usize as decision_tree_builder_impl::BranchBuilder>::Decision),
-    c(<bool as decision_tree_builder_impl::BranchBuilder>::Decision),
-    d(<bool as decision_tree_builder_impl::BranchBuilder>::Decision),
+    Fa(<usize as decision_tree_builder_core::BranchBuilder>::Decision),
+    Fb(<usize as decision_tree_builder_core::BranchBuilder>::Decision),
+    Fc(<bool as decision_tree_builder_core::BranchBuilder>::Decision),
+    Fd(<bool as decision_tree_builder_core::BranchBuilder>::Decision),
 }
-impl decision_tree_builder_impl::Decision for __TestDataDecision {
-    fn to_decision_eval(&self) -> &decision_tree_builder_impl::DecisionEval {
+impl decision_tree_builder_core::Decision for __TestDataDecision {
+    fn to_decision_eval(&self) -> &decision_tree_builder_core::DecisionEval {
         return match self {
-            __TestDataDecision::a(inner) => inner.to_decision_eval(),
-            __TestDataDecision::b(inner) => inner.to_decision_eval(),
-            __TestDataDecision::c(inner) => inner.to_decision_eval(),
-            __TestDataDecision::d(inner) => inner.to_decision_eval(),
+            __TestDataDecision::Fa(inner) => inner.to_decision_eval(),
+            __TestDataDecision::Fb(inner) => inner.to_decision_eval(),
+            __TestDataDecision::Fc(inner) => inner.to_decision_eval(),
+            __TestDataDecision::Fd(inner) => inner.to_decision_eval(),
         };
     }
-    fn to_condition(&self, var: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-        use syn::__private::TokenStreamExt;
-        let mut result = proc_macro2::TokenStream::new();
-        result.append_all(var);
-        result.append(proc_macro2::Punct::new('.', proc_macro2::Spacing::Alone));
+}
+impl decision_tree_builder_impl::ToCondition for __TestDataDecision {
+    fn to_condition(
+        &self,
+        var: decision_tree_builder_impl::__private::TokenStream,
+    ) -> decision_tree_builder_impl::__private::TokenStream {
         return match self {
-            __TestDataDecision::a(inner) => {
-                result.append(proc_macro2::Ident::new("a", proc_macro2::Span::call_site()));
-                inner.to_condition(result)
-            }
-            __TestDataDecision::b(inner) => {
-                result.append(proc_macro2::Ident::new("b", proc_macro2::Span::call_site()));
-                inner.to_condition(result)
-            }
-            __TestDataDecision::c(inner) => {
-                result.append(proc_macro2::Ident::new("c", proc_macro2::Span::call_site()));
-                inner.to_condition(result)
-            }
-            __TestDataDecision::d(inner) => {
-                result.append(proc_macro2::Ident::new("d", proc_macro2::Span::call_site()));
-                inner.to_condition(result)
-            }
+            __TestDataDecision::Fa(inner) => decision_tree_builder_impl::ToCondition::to_condition(
+                inner,
+                decision_tree_builder_impl::__private::access_field(var, "a"),
+            ),
+            __TestDataDecision::Fb(inner) => decision_tree_builder_impl::ToCondition::to_condition(
+                inner,
+                decision_tree_builder_impl::__private::access_field(var, "b"),
+            ),
+            __TestDataDecision::Fc(inner) => decision_tree_builder_impl::ToCondition::to_condition(
+                inner,
+                decision_tree_builder_impl::__private::access_field(var, "c"),
+            ),
+            __TestDataDecision::Fd(inner) => decision_tree_builder_impl::ToCondition::to_condition(
+                inner,
+                decision_tree_builder_impl::__private::access_field(var, "d"),
+            ),
         };
     }
 }
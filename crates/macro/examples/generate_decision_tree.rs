@@ -1,7 +1,7 @@
-use decision_tree_builder::BranchBuilder;
-use decision_tree_builder_impl::TreeBuilder;
+use decision_tree_builder::{BranchBuilder, ToCondition};
+use decision_tree_builder_impl::{BuildCode, TreeBuilder};
 
-#[derive(BranchBuilder)]
+#[derive(BranchBuilder, ToCondition)]
 pub struct TestData {
     a: usize,
     b: usize,
@@ -1,3 +1,4 @@
+#[allow(clippy::needless_bool)]
 pub fn decide(val: &TestData) -> bool {
     return if val.b < 1 {
         if val.a < 1 {
@@ -14,13 +15,7 @@ pub fn decide(val: &TestData) -> bool {
     };
 }
 
-struct T {
-    a: usize,
-    b: B,
-}
-
-struct B();
-
+#[allow(dead_code)]
 pub struct TestData {
     a: usize,
     b: usize,
@@ -32,8 +27,3 @@ fn main() {
     let test_data = TestData { a: 1, b: 1, c: true, d: true };
     println!("Decision: {}", decide(&test_data));
 }
-
-fn foo(t: &T) {
-    let a = t.a;
-    let b = t.b;
-}
@@ -1,37 +1,69 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{parse_macro_input, DeriveInput, Error};
 
 mod macro_impl;
 mod struct_field;
 
-#[proc_macro_derive(BranchBuilder)]
+/// The generated code refers to `decision_tree_builder_core` only, so structs can be trained on
+/// without depending on the code generation crate.
+///
+/// Options are given with `#[branch_builder(...)]`:
+/// - `oblique` - also searches splits on a linear combination of the numeric fields
+#[proc_macro_derive(BranchBuilder, attributes(branch_builder))]
 pub fn my_macro_here_derive(input: TokenStream) -> TokenStream {
     // Construct a representation of Rust code as a syntax tree
     // that we can manipulate
-    let ast = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as DeriveInput);
 
-    // Build the trait implementation
-    let token_stream = macro_impl::impl_hello_macro(&ast);
+    // Build the trait implementation and save a copy of it,
+    // reporting any failure as a compile error instead of panicking
+    let result = macro_impl::impl_hello_macro(&ast)
+        .and_then(|token_stream| save_copy(&ast, "", &token_stream).map(|_| token_stream));
 
+    return match result {
+        Ok(token_stream) => token_stream.into(),
+        Err(error) => error.to_compile_error().into(),
+    };
+}
+
+/// Implements rendering of the decisions generated by the `BranchBuilder` derive,
+/// so the struct can be used to generate code with `decision_tree_builder_impl`, which the generated code refers to.
+#[proc_macro_derive(ToCondition, attributes(branch_builder))]
+pub fn to_condition_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let result = macro_impl::impl_to_condition(&ast)
+        .and_then(|token_stream| save_copy(&ast, "_to_condition", &token_stream).map(|_| token_stream));
+
+    return match result {
+        Ok(token_stream) => token_stream.into(),
+        Err(error) => error.to_compile_error().into(),
+    };
+}
+
+/// Save copy to output folder
+fn save_copy(ast: &DeriveInput, suffix: &str, token_stream: &TokenStream2) -> syn::Result<()> {
+    use std::io::Write;
 
-    // Save copy to output folder
-    {
-        use std::io::Write;
+    let to_error = |error: std::io::Error| {
+        Error::new_spanned(&ast.ident, format!("failed to save generated implementation: {error}"))
+    };
 
-        let name = ast.ident.to_string();
+    let name = ast.ident.to_string();
 
-        let generated_ast = syn::parse(token_stream.clone()).unwrap();
-        let formatted = prettyplease::unparse(&generated_ast);
-        let output_folder = format!(
-            "{}/decision_trees/",
-            std::env::var("OUT_DIR").unwrap_or(String::from("target"))
-        );
-        let output_filename = format!("{}/{}.rs", output_folder, name);
-        std::fs::create_dir_all(output_folder).unwrap();
-        let mut file = std::fs::File::create(output_filename).unwrap();
-        file.write_all(formatted.as_bytes()).unwrap();
-    }
+    let generated_ast = syn::parse2(token_stream.clone())?;
+    let formatted = prettyplease::unparse(&generated_ast);
+    let output_folder = format!(
+        "{}/decision_trees/",
+        std::env::var("OUT_DIR").unwrap_or(String::from("target"))
+    );
+    let output_filename = format!("{}/{}{}.rs", output_folder, name, suffix);
+    std::fs::create_dir_all(output_folder).map_err(to_error)?;
+    let mut file = std::fs::File::create(output_filename).map_err(to_error)?;
+    file.write_all(formatted.as_bytes()).map_err(to_error)?;
 
-    return token_stream;
+    return Ok(());
 }
@@ -1,111 +1,253 @@
-use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::Data::Struct;
-use syn::__private::TokenStream2;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::Data::{Enum, Struct, Union};
+use syn::{Error, Ident, Token};
 
 use crate::struct_field::StructField;
 
-pub fn impl_hello_macro(ast: &syn::DeriveInput) -> TokenStream {
-    let struct_name = &ast.ident;
-    let decision_enum_name = format_ident!("__{struct_name}Decision");
+/// Options given with the `#[branch_builder(...)]` attribute.
+#[derive(Default)]
+struct DeriveOptions {
+    oblique: bool,
+}
 
-    if let Struct(data_struct) = &ast.data {
-        let fields = StructField::from_fields(&data_struct.fields);
-
-        let mut find_best_decision = TokenStream2::new();
-        let mut split_data_match = TokenStream2::new();
-        let mut decision_enum_options = TokenStream2::new();
-        let mut decision_enum_to_decision_eval_match = TokenStream2::new();
-        let mut decision_enum_to_condition_match = TokenStream2::new();
-
-        for field in fields {
-            let named_field = field.named_field;
-            let struct_field = field.struct_field;
-            let field_type = field.field_type;
-            let struct_field_string = struct_field.to_string();
-
-            find_best_decision = quote!(
-                #find_best_decision
-                #decision_enum_name::#named_field(decision_tree_builder_impl::BranchBuilder::find_best_decision(entropy, data, |d| &extract(d).#struct_field)),
-            );
-
-            split_data_match = quote!(
-                #split_data_match
-                #decision_enum_name::#named_field(inner) => decision_tree_builder_impl::BranchBuilder::split_data(data, |d| &extract(d).#struct_field, inner),
-            );
-
-            decision_enum_options = quote!(
-                #decision_enum_options
-                #named_field(<#field_type as decision_tree_builder_impl::BranchBuilder>::Decision),
-            );
-
-            decision_enum_to_decision_eval_match = quote! (
-                #decision_enum_to_decision_eval_match
-                #decision_enum_name::#named_field(inner) => inner.to_decision_eval(),
-            );
-
-            decision_enum_to_condition_match = quote!(
-                #decision_enum_to_condition_match
-                #decision_enum_name::#named_field(inner) => {
-                    result.append(proc_macro2::Ident::new(#struct_field_string, proc_macro2::Span::call_site()));
-                    inner.to_condition(result)
-                }
-            );
+fn parse_options(ast: &syn::DeriveInput) -> syn::Result<DeriveOptions> {
+    let mut options = DeriveOptions::default();
+
+    for attr in ast.attrs.iter().filter(|attr| attr.path.is_ident("branch_builder")) {
+        for option in attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)? {
+            match option.to_string().as_str() {
+                "oblique" => options.oblique = true,
+                _ => return Err(Error::new_spanned(option, "unknown branch_builder option, expected `oblique`")),
+            }
         }
+    }
 
+    return Ok(options);
+}
 
-        let gen = quote! {
-            impl decision_tree_builder_impl::BranchBuilder for #struct_name {
-                type Decision = #decision_enum_name;
+/// Returns the numeric fields combined by oblique splits.
+fn oblique_fields<'a>(ast: &syn::DeriveInput, fields: &'a [StructField]) -> syn::Result<Vec<&'a StructField>> {
+    let numeric_fields: Vec<&StructField> = fields.iter().filter(|field| field.is_numeric()).collect();
+    if numeric_fields.len() < 2 {
+        return Err(Error::new_spanned(&ast.ident, "oblique splits need at least two numeric fields"));
+    }
+    return Ok(numeric_fields);
+}
 
-                fn find_best_decision<R: Copy + Eq + std::hash::Hash, F, D>(entropy: f64, data: &mut [(D, R)], extract: F) -> Self::Decision
-                where F: Fn(&D) -> &Self {
-                    use decision_tree_builder_impl::Decision;
+fn parse_fields(ast: &syn::DeriveInput, derive_name: &str) -> syn::Result<Vec<StructField>> {
+    let struct_name = &ast.ident;
+
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(&ast.generics, format!("{derive_name} cannot be derived for generic structs")));
+    }
+
+    let data_struct = match &ast.data {
+        Struct(data_struct) => data_struct,
+        Enum(data_enum) => {
+            return Err(Error::new_spanned(data_enum.enum_token, format!("{derive_name} can only be derived for structs")));
+        }
+        Union(data_union) => {
+            return Err(Error::new_spanned(data_union.union_token, format!("{derive_name} can only be derived for structs")));
+        }
+    };
+
+    if data_struct.fields.is_empty() {
+        return Err(Error::new_spanned(struct_name, format!("{derive_name} cannot be derived for a struct without fields")));
+    }
+
+    return Ok(StructField::from_fields(&data_struct.fields));
+}
 
-                    let decisions= [
-                        #find_best_decision
-                    ];
+pub fn impl_hello_macro(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name = &ast.ident;
+    let decision_enum_name = format_ident!("__{struct_name}Decision");
+    let fields = parse_fields(ast, "BranchBuilder")?;
+    let options = parse_options(ast)?;
+
+    let mut find_best_decision = TokenStream2::new();
+    let mut matches_match = TokenStream2::new();
+    let mut decision_enum_options = TokenStream2::new();
+    let mut decision_enum_to_decision_eval_match = TokenStream2::new();
+
+    let field_count = fields.len();
+    let mut candidates = quote!(decisions.into_iter());
+
+    if options.oblique {
+        let oblique_fields = oblique_fields(ast, &fields)?;
+        let oblique_count = oblique_fields.len();
+        let struct_fields: Vec<&TokenStream2> = oblique_fields.iter().map(|field| &field.struct_field).collect();
+
+        // Oblique splits come first, so single field splits are preferred when equally good
+        candidates = quote!(
+            std::iter::once({
+                let (classes, class_count) = decision_tree_builder_core::utils::class_indices(data);
+                let vals: Vec<([f64; #oblique_count], usize)> = data
+                    .iter()
+                    .zip(classes)
+                    .map(|((d, _), class)| {
+                        let val = extract(d);
+                        return ([#(val.#struct_fields as f64),*], class);
+                    })
+                    .collect();
+                #decision_enum_name::Oblique(decision_tree_builder_core::utils::find_best_oblique(search.builder.criterion, entropy, &vals, class_count))
+            })
+            .chain(decisions)
+        );
+
+        matches_match = quote!(
+            #decision_enum_name::Oblique(inner) => inner.matches(&[#(self.#struct_fields as f64),*]),
+        );
+
+        decision_enum_options = quote!(
+            Oblique(decision_tree_builder_core::ObliqueDecision<#oblique_count>),
+        );
+
+        decision_enum_to_decision_eval_match = quote!(
+            #decision_enum_name::Oblique(inner) => inner.to_decision_eval(),
+        );
+    }
 
-                    return decisions.into_iter()
-                        .max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval()))
-                        .unwrap();
-                }
+    for (index, field) in fields.into_iter().enumerate() {
+        let named_field = field.named_field;
+        let struct_field = field.struct_field;
+        let field_type = field.field_type;
+        // Errors about field types not implementing `BranchBuilder` point at the field
+        let span = field_type.span();
+
+        find_best_decision = quote_spanned!(span=>
+            #find_best_decision
+            #index => #decision_enum_name::#named_field(decision_tree_builder_core::BranchBuilder::find_best_decision(&search.field(#index), entropy, data, |d| &extract(d).#struct_field)),
+        );
+
+        matches_match = quote_spanned!(span=>
+            #matches_match
+            #decision_enum_name::#named_field(inner) => decision_tree_builder_core::BranchBuilder::matches(&self.#struct_field, inner),
+        );
+
+        decision_enum_options = quote_spanned!(span=>
+            #decision_enum_options
+            #named_field(<#field_type as decision_tree_builder_core::BranchBuilder>::Decision),
+        );
+
+        decision_enum_to_decision_eval_match = quote_spanned! (span=>
+            #decision_enum_to_decision_eval_match
+            #decision_enum_name::#named_field(inner) => inner.to_decision_eval(),
+        );
+    }
 
-                fn split_data<F, D, R>(data: &mut [(D, R)], extract: F, decision: &Self::Decision) -> usize
-                where F: Fn(&D) -> &Self {
-                    return match decision {
-                        #split_data_match
-                    };
-                }
+    let gen = quote! {
+        impl decision_tree_builder_core::BranchBuilder for #struct_name {
+            type Decision = #decision_enum_name;
+
+            fn find_best_decision<'a, R, F, D>(search: &decision_tree_builder_core::FeatureSearch, entropy: f64, data: &'a [(D, R)], extract: F) -> Self::Decision
+            where
+                R: Copy + Eq + std::hash::Hash + decision_tree_builder_core::MaybeSync,
+                F: Fn(&'a D) -> &'a Self + decision_tree_builder_core::MaybeSync,
+                Self: 'a,
+                D: decision_tree_builder_core::MaybeSync,
+            {
+                use decision_tree_builder_core::Decision;
+
+                // Fields are searched in parallel with the `rayon` feature, keeping field order
+                let decisions = decision_tree_builder_core::parallel::map(#field_count, |i| match i {
+                    #find_best_decision
+                    _ => unreachable!(),
+                });
+
+                return #candidates
+                    .max_by(|a, b| a.to_decision_eval().cmp(b.to_decision_eval()))
+                    .unwrap();
             }
 
-            pub enum #decision_enum_name {
-                #decision_enum_options
+            fn matches(&self, decision: &Self::Decision) -> bool {
+                return match decision {
+                    #matches_match
+                };
             }
+        }
 
-            impl decision_tree_builder_impl::Decision for #decision_enum_name {
-                fn to_decision_eval(&self) -> &decision_tree_builder_impl::DecisionEval {
-                    return match self {
-                        #decision_enum_to_decision_eval_match
-                    };
-                }
-
-                fn to_condition(&self, var: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-                    use syn::__private::TokenStreamExt;
-
-                    let mut result = proc_macro2::TokenStream::new();
-                    result.append_all(var);
-                    result.append(proc_macro2::Punct::new('.', proc_macro2::Spacing::Alone));
-                    return match self {
-                        #decision_enum_to_condition_match
-                    };
-                }
+        pub enum #decision_enum_name {
+            #decision_enum_options
+        }
+
+        impl decision_tree_builder_core::Decision for #decision_enum_name {
+            fn to_decision_eval(&self) -> &decision_tree_builder_core::DecisionEval {
+                return match self {
+                    #decision_enum_to_decision_eval_match
+                };
             }
+        }
 
-        };
+    };
+
+    return Ok(gen);
+}
 
-        return gen.into();
+pub fn impl_to_condition(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name = &ast.ident;
+    let decision_enum_name = format_ident!("__{struct_name}Decision");
+    let fields = parse_fields(ast, "ToCondition")?;
+    let options = parse_options(ast)?;
+
+    let mut decision_enum_to_condition_match = TokenStream2::new();
+    let mut decision_enum_to_comparison_match = TokenStream2::new();
+
+    if options.oblique {
+        let struct_field_strings = oblique_fields(ast, &fields)?.into_iter().map(|field| field.struct_field.to_string());
+
+        decision_enum_to_condition_match = quote!(
+            #decision_enum_name::Oblique(inner) => decision_tree_builder_impl::oblique_condition(
+                inner,
+                [#(decision_tree_builder_impl::__private::access_field(var.clone(), #struct_field_strings)),*],
+            ),
+        );
+        decision_enum_to_comparison_match = quote!(#decision_enum_name::Oblique(_) => std::option::Option::None,);
     }
 
-    panic!("Not a struct");
+    for field in fields {
+        let named_field = field.named_field;
+        let struct_field_string = field.struct_field.to_string();
+
+        decision_enum_to_condition_match = quote!(
+            #decision_enum_to_condition_match
+            #decision_enum_name::#named_field(inner) => decision_tree_builder_impl::ToCondition::to_condition(
+                inner,
+                decision_tree_builder_impl::__private::access_field(var, #struct_field_string),
+            ),
+        );
+        decision_enum_to_comparison_match = quote!(
+            #decision_enum_to_comparison_match
+            #decision_enum_name::#named_field(inner) => decision_tree_builder_impl::ToCondition::to_comparison(
+                inner,
+                decision_tree_builder_impl::__private::access_field(var, #struct_field_string),
+            ),
+        );
+    }
+
+    let gen = quote! {
+        impl decision_tree_builder_impl::ToCondition for #decision_enum_name {
+            fn to_condition(
+                &self,
+                var: decision_tree_builder_impl::__private::TokenStream,
+            ) -> decision_tree_builder_impl::__private::TokenStream {
+                return match self {
+                    #decision_enum_to_condition_match
+                };
+            }
+
+            fn to_comparison(
+                &self,
+                var: decision_tree_builder_impl::__private::TokenStream,
+            ) -> std::option::Option<decision_tree_builder_impl::Comparison> {
+                return match self {
+                    #decision_enum_to_comparison_match
+                };
+            }
+        }
+    };
+
+    return Ok(gen);
 }
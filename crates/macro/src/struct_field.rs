@@ -5,35 +5,36 @@ use syn::{Fields, Type};
 pub struct StructField {
     pub struct_field: TokenStream,
     pub named_field: Ident,
-    pub field_type: Ident,
+    pub field_type: Type,
 }
 
 impl StructField {
     pub fn from_fields(fields: &Fields) -> Vec<Self> {
-        return fields
-            .iter()
-            .enumerate()
-            .map(|(i, field)| {
-                let (struct_field, named_field) = if let Some(ident) = &field.ident {
-                    (ident.clone().to_token_stream(), format_ident!("F{ident}"))
-                } else {
-                    (Literal::usize_unsuffixed(i).to_token_stream(), format_ident!("_{i}"))
-                };
+        let mut result = vec![];
 
-                let field_type = Self::type_to_ident(&field.ty);
+        for (i, field) in fields.iter().enumerate() {
+            let (struct_field, named_field) = if let Some(ident) = &field.ident {
+                (ident.clone().to_token_stream(), format_ident!("F{ident}"))
+            } else {
+                (Literal::usize_unsuffixed(i).to_token_stream(), format_ident!("_{i}"))
+            };
 
-                return StructField { struct_field, named_field, field_type };
-            })
-            .collect();
-    }
-
-    fn type_to_ident(ty: &Type) -> Ident {
-        if let Type::Path(type_path) = ty {
-            if let Some(last_segment) = type_path.path.segments.last() {
-                return last_segment.ident.clone();
-            }
+            result.push(StructField { struct_field, named_field, field_type: field.ty.clone() });
         }
 
-        panic!("Cant handle given type");
+        return result;
+    }
+
+    /// Whether the field has a primitive numeric type that can be cast to `f64`.
+    pub fn is_numeric(&self) -> bool {
+        const NUMERIC_TYPES: [&str; 14] =
+            ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64"];
+        return match &self.field_type {
+            Type::Path(type_path) if type_path.qself.is_none() => type_path
+                .path
+                .get_ident()
+                .is_some_and(|ident| NUMERIC_TYPES.contains(&ident.to_string().as_str())),
+            _ => false,
+        };
     }
 }
@@ -0,0 +1,148 @@
+use decision_tree_builder::{BranchBuilder, ToCondition};
+use decision_tree_builder_impl::testing::{assert_code_matches_tree, assert_compiles, assert_generated_matches_tree};
+use decision_tree_builder_impl::{BuildCode, FlatCodeGenerator, Tree, TreeBuilder};
+use quote::quote;
+
+/// Definitions of the types below, for compiling the generated code referring to them.
+const TYPES: &str = "
+pub mod derive {
+    pub struct NamedData { pub a: usize, pub b: bool }
+    pub struct TupleData(pub usize, pub bool);
+    pub struct BoxedData { pub inner: Box<NamedData>, pub name: std::sync::Arc<String> }
+    pub struct PointData { pub x: u32, pub y: f64, pub label: bool }
+}
+";
+
+#[derive(BranchBuilder, ToCondition)]
+pub struct NamedData {
+    a: usize,
+    b: bool,
+}
+
+#[derive(BranchBuilder, ToCondition)]
+pub struct TupleData(usize, bool);
+
+#[derive(BranchBuilder, ToCondition)]
+pub struct BoxedData {
+    inner: Box<NamedData>,
+    name: std::sync::Arc<String>,
+}
+
+#[derive(BranchBuilder, ToCondition)]
+#[branch_builder(oblique)]
+pub struct PointData {
+    x: u32,
+    y: f64,
+    label: bool,
+}
+
+#[test]
+fn test_named_struct() {
+    let mut data = [
+        (NamedData { a: 0, b: true }, 1),
+        (NamedData { a: 1, b: true }, 2),
+        (NamedData { a: 2, b: false }, 3),
+    ];
+    let decision = TreeBuilder::default().build(&mut data).unwrap();
+    let expected = quote!(
+        pub fn decide(val: &derive::NamedData) -> i32 {
+            return if val.b {
+                if val.a < 1 { 1 } else { 2 }
+            } else {
+                3
+            };
+        }
+    );
+    assert_eq!(decision.to_string(), expected.to_string());
+    assert_compiles(&decision, TYPES);
+}
+
+#[test]
+fn test_tuple_struct() {
+    let mut data = [(TupleData(0, true), 1), (TupleData(1, true), 2), (TupleData(2, false), 3)];
+    let decision = TreeBuilder::default().build(&mut data).unwrap();
+    let expected = quote!(
+        pub fn decide(val: &derive::TupleData) -> i32 {
+            return if val.1 {
+                if val.0 < 1 { 1 } else { 2 }
+            } else {
+                3
+            };
+        }
+    );
+    assert_eq!(decision.to_string(), expected.to_string());
+    assert_compiles(&decision, TYPES);
+}
+
+#[test]
+fn test_predict() {
+    let mut data = [
+        (NamedData { a: 0, b: true }, 1),
+        (NamedData { a: 1, b: true }, 2),
+        (NamedData { a: 2, b: false }, 3),
+    ];
+    let tree = TreeBuilder::default().train(&mut data);
+    assert_eq!(tree.predict(&NamedData { a: 0, b: true }), 1);
+    assert_eq!(tree.predict(&NamedData { a: 5, b: true }), 2);
+    assert_eq!(tree.predict(&NamedData { a: 0, b: false }), 3);
+}
+
+#[test]
+fn test_flat_code() {
+    let mut data: Vec<(NamedData, usize)> = (0..12).map(|i| (NamedData { a: i, b: i % 3 == 0 }, i / 4 + (i % 3 == 0) as usize)).collect();
+    let tree = TreeBuilder::default().train(&mut data);
+    let code = FlatCodeGenerator::default().generate(&tree).unwrap();
+    // The thresholds of the field are stored as data
+    assert!(code.to_string().contains("val . a < THRESHOLDS_"), "{code}");
+    let accuracy = assert_code_matches_tree(&code, &tree, &data, TYPES, |NamedData { a, b }| quote!(derive::NamedData { a: #a, b: #b }));
+    assert_eq!(accuracy, 1.0);
+}
+
+#[test]
+fn test_boxed() {
+    let mut data = [
+        (BoxedData { inner: Box::new(NamedData { a: 0, b: true }), name: String::from("x").into() }, 1),
+        (BoxedData { inner: Box::new(NamedData { a: 1, b: true }), name: String::from("x").into() }, 2),
+        (BoxedData { inner: Box::new(NamedData { a: 1, b: true }), name: String::from("y").into() }, 1),
+    ];
+    let tree = TreeBuilder::default().train(&mut data);
+    assert_eq!(tree.predict(&data[0].0), 1);
+    assert_eq!(tree.predict(&data[1].0), 2);
+    assert_eq!(tree.predict(&data[2].0), 1);
+
+    let decision = TreeBuilder::default().build(&mut data).unwrap();
+    assert!(decision.to_string().contains("val . inner . a"));
+    assert_compiles(&decision, TYPES);
+
+    let accuracy = assert_generated_matches_tree(&TreeBuilder::default(), &mut data, TYPES, |boxed| {
+        let NamedData { a, b } = *boxed.inner;
+        let name = boxed.name.as_str();
+        return quote!(derive::BoxedData {
+            inner: Box::new(derive::NamedData { a: #a, b: #b }),
+            name: std::sync::Arc::new(String::from(#name)),
+        });
+    });
+    assert_eq!(accuracy, 1.0);
+}
+
+#[test]
+fn test_oblique() {
+    let mut data: Vec<(PointData, bool)> = (0..100)
+        .map(|i| (PointData { x: i % 10, y: (i / 10) as f64, label: i % 2 == 0 }, i % 10 > i / 10))
+        .collect();
+    let tree = TreeBuilder::default().train(&mut data);
+    assert!(matches!(tree, Tree::Branch { decision: __PointDataDecision::Oblique(_), .. }));
+    for (val, result) in &data {
+        assert_eq!(tree.predict(val), *result);
+    }
+
+    let decision = TreeBuilder::default().build(&mut data).unwrap();
+    assert!(decision.to_string().contains("(val . x as f64) *"));
+    assert_compiles(&decision, TYPES);
+
+    let accuracy = assert_generated_matches_tree(&TreeBuilder::default(), &mut data, TYPES, |point| {
+        let PointData { x, y, label } = point;
+        return quote!(derive::PointData { x: #x, y: #y, label: #label });
+    });
+    assert_eq!(accuracy, 1.0);
+}
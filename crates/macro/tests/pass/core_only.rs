@@ -0,0 +1,18 @@
+use decision_tree_builder::BranchBuilder;
+use decision_tree_builder_core::TreeBuilder;
+
+// Shadows the code generation crate, so the derive only compiles if it refers to the core crate
+#[allow(dead_code)]
+mod decision_tree_builder_impl {}
+
+#[derive(BranchBuilder)]
+pub struct TestData {
+    a: usize,
+    b: bool,
+}
+
+fn main() {
+    let mut data = [(TestData { a: 0, b: true }, 1), (TestData { a: 1, b: false }, 2)];
+    let tree = TreeBuilder::default().train(&mut data);
+    assert_eq!(tree.predict(&data[0].0), 1);
+}
@@ -0,0 +1,28 @@
+use decision_tree_builder_impl::{eq_implementation, ord_implementation, TreeBuilder};
+
+// Shadows the core crate, so the macros only expand if they refer to it through `$crate`
+#[allow(dead_code)]
+mod decision_tree_builder_core {}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+pub struct Level(u8);
+
+ord_implementation!(Level);
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Kind {
+    A,
+    B,
+}
+
+eq_implementation!(Kind);
+
+fn main() {
+    let mut data = [(Level(0), 1), (Level(1), 2)];
+    let tree = TreeBuilder::default().train(&mut data);
+    assert_eq!(tree.predict(&Level(1)), 2);
+
+    let mut data = [(Kind::A, 1), (Kind::B, 2)];
+    let tree = TreeBuilder::default().train(&mut data);
+    assert_eq!(tree.predict(&Kind::B), 2);
+}
@@ -0,0 +1,9 @@
+use decision_tree_builder::BranchBuilder;
+
+#[derive(BranchBuilder)]
+pub enum TestData {
+    A,
+    B,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use decision_tree_builder::BranchBuilder;
+
+#[derive(BranchBuilder)]
+pub struct TestData<T> {
+    a: T,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use decision_tree_builder::BranchBuilder;
+
+#[derive(BranchBuilder)]
+#[branch_builder(oblique)]
+pub struct TooFewNumbers {
+    a: usize,
+    b: bool,
+}
+
+#[derive(BranchBuilder)]
+#[branch_builder(diagonal)]
+pub struct UnknownOption {
+    a: usize,
+    b: usize,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use decision_tree_builder::BranchBuilder;
+
+#[derive(BranchBuilder)]
+pub union TestData {
+    a: u32,
+    b: f32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use decision_tree_builder::BranchBuilder;
+
+#[derive(BranchBuilder)]
+pub struct TestData;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use std::collections::HashMap;
+
+use decision_tree_builder::BranchBuilder;
+
+#[derive(BranchBuilder)]
+pub struct TestData {
+    a: usize,
+    b: HashMap<u8, u8>,
+}
+
+fn main() {}